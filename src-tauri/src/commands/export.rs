@@ -1,31 +1,77 @@
 // Export command for Tauri
+use serde::Serialize;
 use tauri::State;
 
 use crate::export::{ExportPipeline, ExportSettings};
-use crate::export::pipeline::{ClipData, TransitionData, TextOverlayData};
+use crate::export::cache::RenderCache;
+use crate::export::frame::FrameFormat;
+use crate::export::pipeline::{ClipData, TransitionData, TextOverlayData, RedactionData};
+use crate::export::plan::RenderPlan;
+use crate::export::tracks::TrackData;
+use crate::export::transitions::{self, TransitionInfo};
 use crate::ffmpeg::commands::FFmpegState;
 use crate::ffmpeg::wrapper::ExportProgress;
 
+/// Result of export_timeline: the output path, or the render plan of a dry run
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ExportOutput {
+    Path(String),
+    Plan(RenderPlan),
+}
+
 /// Tauri command to export timeline to video file
 ///
-/// Takes timeline clips, transitions, text overlays, and export settings from frontend,
+/// Takes timeline clips, transitions, text overlays, redactions, track settings, and export settings from frontend,
 /// processes them through ExportPipeline, and returns output path.
+/// With settings.dry_run nothing is rendered and the render plan is returned instead.
 #[tauri::command]
 pub fn export_timeline(
     clips: Vec<ClipData>,
     transitions: Vec<TransitionData>,
     text_overlays: Vec<TextOverlayData>,
+    redactions: Vec<RedactionData>,
+    tracks: Vec<TrackData>,
     settings: ExportSettings,
     ffmpeg_state: State<'_, FFmpegState>,
-) -> Result<String, String> {
+) -> Result<ExportOutput, String> {
     // Get FFmpeg wrapper from state
     let ffmpeg_wrapper = ffmpeg_state.get_wrapper()?;
 
     // Create export pipeline
     let pipeline = ExportPipeline::new(std::sync::Arc::new(std::sync::Mutex::new(ffmpeg_wrapper)));
 
+    if settings.dry_run {
+        return pipeline.plan_timeline(clips, transitions, text_overlays, redactions, tracks, settings)
+            .map(ExportOutput::Plan);
+    }
+
     // Execute export (blocking operation)
-    pipeline.export_timeline(clips, transitions, text_overlays, settings)
+    pipeline.export_timeline(clips, transitions, text_overlays, redactions, tracks, settings)
+        .map(ExportOutput::Path)
+}
+
+/// Tauri command to render the composited frame at a timeline timestamp to an image
+///
+/// Renders base clips, overlays, redactions and text at the export resolution (PNG, JPEG
+/// or WebP) for thumbnails and screenshots, and returns the image path.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn export_frame(
+    clips: Vec<ClipData>,
+    transitions: Vec<TransitionData>,
+    text_overlays: Vec<TextOverlayData>,
+    redactions: Vec<RedactionData>,
+    tracks: Vec<TrackData>,
+    settings: ExportSettings,
+    time: f64,
+    output_path: String,
+    format: FrameFormat,
+    ffmpeg_state: State<'_, FFmpegState>,
+) -> Result<String, String> {
+    let ffmpeg_wrapper = ffmpeg_state.get_wrapper()?;
+    let pipeline = ExportPipeline::new(std::sync::Arc::new(std::sync::Mutex::new(ffmpeg_wrapper)));
+    pipeline.export_frame(clips, transitions, text_overlays, redactions, tracks, &settings, time, &output_path, format)
 }
 
 /// Tauri command to get current export progress
@@ -39,3 +85,20 @@ pub fn get_export_progress(ffmpeg_state: State<'_, FFmpegState>) -> Result<Expor
     // Get current progress
     Ok(ffmpeg_wrapper.get_progress())
 }
+
+/// Tauri command to list the available transitions
+///
+/// Frontend uses this to populate the transition picker with each transition's
+/// allowed directions and options
+#[tauri::command]
+pub fn list_transitions() -> Result<Vec<TransitionInfo>, String> {
+    Ok(transitions::list_transitions())
+}
+
+/// Tauri command to delete every cached intermediate render
+///
+/// Returns the number of bytes freed
+#[tauri::command]
+pub fn clear_render_cache() -> Result<u64, String> {
+    RenderCache::open_default().clear()
+}
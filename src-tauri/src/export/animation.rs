@@ -0,0 +1,257 @@
+// Animated image output: GIF and animated WebP
+//
+// The timeline is rendered to an MP4 in a job workspace as for any export (ranges, tracks
+// and overlays all apply), then converted. GIFs take two passes: palettegen builds a
+// 256 color palette from the whole clip and paletteuse maps every frame onto it, which
+// looks far better than FFmpeg's default fixed palette. WebP is full color and takes one pass.
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::capabilities::Feature;
+use crate::ffmpeg::workspace::JobWorkspace;
+use super::encoder::ExportSettings;
+use super::pipeline::{ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData};
+use super::plan::ExportRoute;
+use super::tracks::TrackData;
+
+/// Animated image formats
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationFormat {
+    Gif,
+    Webp,
+}
+
+/// How paletteuse spreads the color error of 256 color GIFs
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dither {
+    None,               // Flat color bands; smallest files, fine for screen recordings
+    Bayer,              // Ordered pattern; compresses well, strength set by bayer_scale
+    FloydSteinberg,
+    #[default]
+    Sierra2_4a,         // FFmpeg's default; smooth gradients
+}
+
+impl Dither {
+    fn name(&self) -> &'static str {
+        match self {
+            Dither::None => "none",
+            Dither::Bayer => "bayer",
+            Dither::FloydSteinberg => "floyd_steinberg",
+            Dither::Sierra2_4a => "sierra2_4a",
+        }
+    }
+}
+
+/// Settings for exporting the timeline as an animated image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationSettings {
+    pub format: AnimationFormat,
+    #[serde(default = "default_fps")]
+    pub fps: f64,                       // Frame rate of the animation
+    #[serde(default)]
+    pub max_width: Option<u32>,         // Scale down to this width, keeping the aspect ratio (None = export width)
+    #[serde(default)]
+    pub dither: Dither,                 // GIF only
+    #[serde(default = "default_bayer_scale")]
+    pub bayer_scale: u32,               // 0-5, with Bayer dithering; higher is less visible but bands more
+    #[serde(default)]
+    pub plays: u32,                     // Times the animation plays (0 = loop forever)
+}
+
+fn default_fps() -> f64 {
+    15.0
+}
+
+fn default_bayer_scale() -> u32 {
+    2
+}
+
+impl AnimationSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.fps.is_finite() && self.fps > 0.0 && self.fps <= 50.0) {
+            return Err(format!("Animation frame rate must be between 0 and 50 fps, got {}", self.fps));
+        }
+        if let Some(width) = self.max_width {
+            if !(16..=4096).contains(&width) {
+                return Err(format!("Animation width must be between 16 and 4096 pixels, got {}", width));
+            }
+        }
+        if self.bayer_scale > 5 {
+            return Err(format!("Bayer scale must be between 0 and 5, got {}", self.bayer_scale));
+        }
+        Ok(())
+    }
+
+    /// Encoders, filters and muxers the conversion uses
+    pub fn required_features(&self) -> Vec<(Feature, &'static str)> {
+        let mut required = vec![(Feature::Filter("fps"), "the animation frame rate")];
+        if self.max_width.is_some() {
+            required.push((Feature::Filter("scale"), "the animation width"));
+        }
+        match self.format {
+            AnimationFormat::Gif => required.extend([
+                (Feature::Filter("palettegen"), "GIF palettes"),
+                (Feature::Filter("paletteuse"), "GIF palettes"),
+                (Feature::Encoder("gif"), "GIF output"),
+                (Feature::Muxer("gif"), "GIF output"),
+            ]),
+            AnimationFormat::Webp => required.extend([
+                (Feature::Encoder("libwebp_anim"), "animated WebP output"),
+                (Feature::Muxer("webp"), "animated WebP output"),
+            ]),
+        }
+        required
+    }
+
+    /// Frame rate and size filters (used by both GIF passes and the WebP encode)
+    ///
+    /// Example: "fps=15,scale='min(480,iw)':-1:flags=lanczos"
+    fn frame_filters(&self) -> String {
+        let mut filters = vec![format!("fps={}", self.fps)];
+        if let Some(width) = self.max_width {
+            // Never scale up; lanczos keeps text in screen recordings readable
+            filters.push(format!("scale='min({},iw)':-1:flags=lanczos", width));
+        }
+        filters.join(",")
+    }
+
+    /// paletteuse filter with the chosen dithering
+    ///
+    /// Example: "paletteuse=dither=bayer:bayer_scale=2:diff_mode=rectangle"
+    fn paletteuse_filter(&self) -> String {
+        let mut filter = format!("paletteuse=dither={}", self.dither.name());
+        if self.dither == Dither::Bayer {
+            filter.push_str(&format!(":bayer_scale={}", self.bayer_scale));
+        }
+        // Only re-map the changed rectangle of each frame, matching palettegen's stats_mode=diff
+        filter.push_str(":diff_mode=rectangle");
+        filter
+    }
+
+    /// Value of the muxer's -loop option
+    ///
+    /// The GIF muxer counts repeats after the first play, with -1 for playing once (0 would
+    /// loop forever); WebP counts plays. Both use 0 for looping forever.
+    fn loop_value(&self) -> i64 {
+        match (self.format, self.plays) {
+            (_, 0) => 0,
+            (AnimationFormat::Gif, 1) => -1,
+            (AnimationFormat::Gif, plays) => plays as i64 - 1,
+            (AnimationFormat::Webp, plays) => plays as i64,
+        }
+    }
+}
+
+impl ExportPipeline {
+    /// Render the timeline and convert it to the animated image at settings.output_path
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn render_animation(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: ExportSettings,
+        animation: &AnimationSettings,
+    ) -> Result<(ExportRoute, f64), String> {
+        animation.validate()?;
+        eprintln!("🎞️  Exporting {:?} at {} fps (max width {:?}) to {}", animation.format, animation.fps, animation.max_width, settings.output_path);
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        ffmpeg.require(&animation.required_features())?;
+
+        let workspace = JobWorkspace::create("animation")?;
+        let video_path = workspace.file("animation_source.mp4").display().to_string();
+        let output_path = settings.output_path.clone();
+        let video_settings = ExportSettings {
+            output_path: video_path.clone(),
+            animation: None,
+            ..settings
+        };
+        let rendered = self.render_timeline(clips, transitions, text_overlays, redactions, tracks, video_settings)?;
+
+        let frame_filters = animation.frame_filters();
+        let loop_value = animation.loop_value().to_string();
+        match animation.format {
+            AnimationFormat::Gif => {
+                ffmpeg.set_progress(90.0, "Building GIF palette...".to_string(), None);
+                let palette_path = workspace.file("palette.png").display().to_string();
+                let palettegen = format!("{},palettegen=stats_mode=diff", frame_filters);
+                ffmpeg.execute_command(&["-i", &video_path, "-vf", &palettegen, "-y", &palette_path])?;
+
+                ffmpeg.set_progress(95.0, "Encoding GIF...".to_string(), None);
+                let graph = format!("[0:v]{}[frames];[frames][1:v]{}[vout]", frame_filters, animation.paletteuse_filter());
+                ffmpeg.execute_command(&[
+                    "-i", &video_path,
+                    "-i", &palette_path,
+                    "-filter_complex", &graph,
+                    "-map", "[vout]",
+                    "-loop", &loop_value,
+                    "-f", "gif",
+                    "-y", &output_path,
+                ])?;
+            }
+            AnimationFormat::Webp => {
+                ffmpeg.set_progress(90.0, "Encoding WebP...".to_string(), None);
+                ffmpeg.execute_command(&[
+                    "-i", &video_path,
+                    "-vf", &frame_filters,
+                    "-an",
+                    "-c:v", "libwebp_anim",
+                    "-quality", "80",
+                    "-loop", &loop_value,
+                    "-f", "webp",
+                    "-y", &output_path,
+                ])?;
+            }
+        }
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(format: AnimationFormat) -> AnimationSettings {
+        serde_json::from_value(serde_json::json!({ "format": format })).unwrap()
+    }
+
+    #[test]
+    fn test_gif_filters_and_loops() {
+        let mut gif = animation(AnimationFormat::Gif);
+        assert_eq!((gif.fps, gif.dither, gif.plays), (15.0, Dither::Sierra2_4a, 0));
+        assert_eq!(gif.frame_filters(), "fps=15");
+
+        gif.max_width = Some(480);
+        gif.dither = Dither::Bayer;
+        assert_eq!(gif.frame_filters(), "fps=15,scale='min(480,iw)':-1:flags=lanczos");
+        assert_eq!(gif.paletteuse_filter(), "paletteuse=dither=bayer:bayer_scale=2:diff_mode=rectangle");
+
+        // Played once: no repeats for GIF, one play for WebP
+        gif.plays = 1;
+        let mut webp = animation(AnimationFormat::Webp);
+        webp.plays = 1;
+        assert_eq!((gif.loop_value(), webp.loop_value()), (-1, 1));
+        gif.plays = 3;
+        assert_eq!(gif.loop_value(), 2);
+    }
+
+    #[test]
+    fn test_invalid_animation_settings() {
+        let mut gif = animation(AnimationFormat::Gif);
+        assert!(gif.validate().is_ok());
+        gif.fps = 0.0;
+        assert!(gif.validate().is_err());
+        gif.fps = 10.0;
+        gif.bayer_scale = 6;
+        assert!(gif.validate().is_err());
+    }
+}
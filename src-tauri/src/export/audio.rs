@@ -0,0 +1,175 @@
+// Audio-only export: the stereo timeline mix, without encoding any video
+//
+// The mix is built by the single-pass graph (see graph.rs) with every clip's video switched
+// off, so volumes, fades, J/L cuts, track mute/solo/gain/pan and the master bus are exactly
+// those of a video export. The graph's placeholder video is discarded by a sink.
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::capabilities::Feature;
+use super::encoder::{ExportSettings, Resolution};
+use super::graph::{self, SourceInfo};
+use super::layout::LayoutTemplate;
+use super::pipeline::{ClipData, ExportPipeline, TransitionData};
+use super::tracks::TrackData;
+
+/// Audio file formats
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    Mp3,
+    Aac,
+    Opus,
+    Flac,
+    Wav,
+}
+
+impl AudioFormat {
+    fn encoder(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "libmp3lame",
+            AudioFormat::Aac => "aac",
+            AudioFormat::Opus => "libopus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "pcm_s16le",
+        }
+    }
+
+    /// Bitrate used when none is set (kbps); None for lossless formats
+    fn default_bitrate(&self) -> Option<u32> {
+        match self {
+            AudioFormat::Mp3 | AudioFormat::Aac => Some(192),
+            AudioFormat::Opus => Some(128),
+            AudioFormat::Flac | AudioFormat::Wav => None,
+        }
+    }
+}
+
+/// Settings for exporting only the timeline audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub format: AudioFormat,
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,  // Lossy formats only (None = format default)
+}
+
+impl AudioSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(bitrate) = self.bitrate_kbps {
+            if !(32..=512).contains(&bitrate) {
+                return Err(format!("Audio bitrate must be between 32 and 512 kbps, got {}", bitrate));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encoder arguments: 48 kHz stereo, the format of the mix
+    ///
+    /// Example: ["-c:a", "libmp3lame", "-b:a", "192k", "-ar", "48000", "-ac", "2"]
+    fn encoder_args(&self) -> Vec<String> {
+        let mut args = vec!["-c:a".to_string(), self.format.encoder().to_string()];
+        if let Some(bitrate) = self.format.default_bitrate().map(|default| self.bitrate_kbps.unwrap_or(default)) {
+            args.push("-b:a".to_string());
+            args.push(format!("{}k", bitrate));
+        }
+        args.extend(["-ar", "48000", "-ac", "2"].iter().map(|s| s.to_string()));
+        args
+    }
+}
+
+impl ExportPipeline {
+    /// Render the mixed timeline audio to settings.output_path
+    ///
+    /// clips are prepared as in export_timeline. Transitions are cuts in the mix (the
+    /// single-pass graph doesn't crossfade), so the audio keeps the timeline's length.
+    pub(super) fn export_audio_only(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+        audio: &AudioSettings,
+    ) -> Result<String, String> {
+        audio.validate()?;
+        eprintln!("🔊 Exporting {:?} audio to {}", audio.format, settings.output_path);
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        ffmpeg.require(&[(Feature::Encoder(audio.format.encoder()), "this audio format")])?;
+
+        if !clips.iter().any(|clip| clip.audio_enabled) {
+            return Err("No clips with audio to export".to_string());
+        }
+
+        ffmpeg.set_progress(5.0, "Probing sources...".to_string(), None);
+        let sources = graph::probe_sources(&ffmpeg, clips)?;
+        let mut args = self.audio_mix_args(clips, transitions, tracks, settings, &sources)?;
+        args.extend(audio.encoder_args());
+        args.push("-y".to_string());
+        args.push(settings.output_path.clone());
+
+        ffmpeg.set_progress(10.0, "Encoding audio...".to_string(), None);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(settings.output_path.clone())
+    }
+
+    /// FFmpeg inputs, graph and -map of the timeline's audio mix, up to the encoder arguments
+    ///
+    /// Every clip keeps its place, with its video switched off: silent clips still hold the
+    /// timing of the clips after them. The mix is trimmed to the timeline length.
+    pub(super) fn audio_mix_args(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+        sources: &HashMap<String, SourceInfo>,
+    ) -> Result<Vec<String>, String> {
+        // Without video the graph only decodes audio; layout and resolution don't matter
+        let clips: Vec<ClipData> = clips.iter()
+            .map(|clip| ClipData { video_enabled: false, ..clip.clone() })
+            .collect();
+        let graph_settings = ExportSettings {
+            resolution: Resolution::Source,
+            layout: LayoutTemplate::default(),
+            ..settings.clone()
+        };
+
+        let graph = self.build_single_pass_graph(&clips, transitions, &[], &[], tracks, &graph_settings, sources)?;
+        eprintln!("🔊 AUDIO: {} inputs, filter graph: {}", graph.inputs.len(), graph.filter_complex);
+
+        let mut args: Vec<String> = graph.inputs.iter().flatten().cloned().collect();
+        args.push("-filter_complex".to_string());
+        args.push(format!("{};{}nullsink", graph.filter_complex, graph.video_label));
+        args.push("-map".to_string());
+        args.push(graph.audio_label.clone());
+        args.push("-t".to_string());
+        args.push(format!("{:.3}", graph.duration));
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_encoder_args() {
+        let mp3 = AudioSettings { format: AudioFormat::Mp3, bitrate_kbps: None };
+        assert_eq!(mp3.encoder_args(), vec!["-c:a", "libmp3lame", "-b:a", "192k", "-ar", "48000", "-ac", "2"]);
+
+        // Lossless formats have no bitrate
+        let flac = AudioSettings { format: AudioFormat::Flac, bitrate_kbps: Some(320) };
+        assert_eq!(flac.encoder_args(), vec!["-c:a", "flac", "-ar", "48000", "-ac", "2"]);
+
+        let opus: AudioSettings = serde_json::from_str(r#"{"format":"opus","bitrate_kbps":96}"#).unwrap();
+        assert!(opus.encoder_args().contains(&"96k".to_string()));
+        assert!(AudioSettings { bitrate_kbps: Some(8), ..opus }.validate().is_err());
+    }
+}
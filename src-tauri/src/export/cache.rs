@@ -0,0 +1,249 @@
+// Persistent cache of rendered intermediate segments
+//
+// Entries are named by a hash of everything that determines the rendered bytes: the
+// source file's identity (path, size, mtime), the trimmed range, filters and encoding
+// parameters. A re-export after an unrelated edit (e.g. a text overlay) finds the
+// unchanged segments already rendered. The least recently used entries are evicted
+// once the cache grows past its size cap.
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size cap for the cache directory
+pub const DEFAULT_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Bump when the way segments are rendered changes, so old entries stop matching
+const CACHE_VERSION: u32 = 1;
+
+/// Extension of cache entries (all intermediates are MP4)
+const ENTRY_EXTENSION: &str = "mp4";
+
+/// Directory of rendered segments with LRU eviction
+#[derive(Debug, Clone)]
+pub struct RenderCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl RenderCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// Cache in the app data directory (next to the database), falling back to the temp dir
+    pub fn open_default() -> Self {
+        let base = tauri::api::path::data_dir()
+            .map(|dir| dir.join("ClipForge"))
+            .unwrap_or_else(std::env::temp_dir);
+        Self::new(base.join("render_cache"), DEFAULT_MAX_BYTES)
+    }
+
+    /// Cache key for a segment from the parts that determine its content
+    ///
+    /// Parts are joined unambiguously and hashed with two 64-bit FNV-1a passes
+    /// (different offset bases), giving a 32 hex digit name.
+    pub fn key(parts: &[String]) -> String {
+        let mut input = format!("v{}", CACHE_VERSION);
+        for part in parts {
+            input.push_str(&format!("\u{1f}{}:{}", part.len(), part));
+        }
+        format!(
+            "{:016x}{:016x}",
+            fnv1a(input.as_bytes(), 0xcbf2_9ce4_8422_2325),
+            fnv1a(input.as_bytes(), 0x6c62_272e_07bb_0142)
+        )
+    }
+
+    /// Identity of a source file: canonical path, size and modification time
+    /// Replacing or editing the file changes the identity, so stale renders are never reused
+    pub fn source_identity(path: &str) -> Result<String, String> {
+        let canonical = std::fs::canonicalize(path)
+            .map_err(|e| format!("Failed to resolve {}: {}", path, e))?;
+        let metadata = std::fs::metadata(&canonical)
+            .map_err(|e| format!("Failed to read metadata of {}: {}", path, e))?;
+        let modified = metadata.modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Ok(format!("{}|{}|{}", canonical.display(), metadata.len(), modified))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, ENTRY_EXTENSION))
+    }
+
+    /// Link or copy a cached segment to destination if the key is present
+    /// Returns whether it was a hit; a hit marks the entry as recently used
+    /// Callers must delete (not overwrite) destination, as it may share the entry's data
+    pub fn fetch(&self, key: &str, destination: &Path) -> bool {
+        let entry = self.entry_path(key);
+        if !entry.exists() {
+            return false;
+        }
+        if link_or_copy(&entry, destination).is_err() {
+            return false;
+        }
+        touch(&entry);
+        true
+    }
+
+    /// Store a rendered segment under key, then evict down to the size cap
+    pub fn store(&self, key: &str, rendered: &Path) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create render cache directory: {}", e))?;
+
+        // Copy (not link) so later writes to the working file can't change the entry, and
+        // write under a temporary name so a concurrent fetch never sees a partial file
+        let entry = self.entry_path(key);
+        let partial = self.dir.join(format!("{}.partial", key));
+        std::fs::copy(rendered, &partial)
+            .map_err(|e| format!("Failed to copy {} into the render cache: {}", rendered.display(), e))?;
+        std::fs::rename(&partial, &entry)
+            .map_err(|e| format!("Failed to store render cache entry: {}", e))?;
+        touch(&entry);
+
+        self.evict()
+    }
+
+    /// Entries as (path, size, last used), oldest first
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(ENTRY_EXTENSION))
+                .filter_map(|path| {
+                    let metadata = std::fs::metadata(&path).ok()?;
+                    let used = metadata.modified().unwrap_or(UNIX_EPOCH);
+                    Some((path, metadata.len(), used))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        entries.sort_by_key(|(_, _, used)| *used);
+        entries
+    }
+
+    /// Remove least recently used entries until the cache fits its size cap
+    pub fn evict(&self) -> Result<(), String> {
+        let entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        for (path, size, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            eprintln!("🗑️  Render cache over {} bytes, evicting {}", self.max_bytes, path.display());
+            if std::fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every entry; returns the number of bytes freed
+    pub fn clear(&self) -> Result<u64, String> {
+        let mut freed = 0;
+        for (path, size, _) in self.entries() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            freed += size;
+        }
+        Ok(freed)
+    }
+}
+
+/// 64-bit FNV-1a with a given offset basis
+fn fnv1a(bytes: &[u8], offset_basis: u64) -> u64 {
+    bytes.iter().fold(offset_basis, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Hard link when source and destination share a filesystem, copy otherwise
+fn link_or_copy(from: &Path, to: &Path) -> Result<(), String> {
+    let _ = std::fs::remove_file(to);
+    if std::fs::hard_link(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {} to {}: {}", from.display(), to.display(), e))
+}
+
+/// Mark an entry as used now (modification time is the LRU clock)
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn temp_cache(max_bytes: u64) -> (RenderCache, PathBuf) {
+        let root = std::env::temp_dir().join(format!("clipforge_cache_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        (RenderCache::new(root.join("cache"), max_bytes), root)
+    }
+
+    #[test]
+    fn test_cache_keys() {
+        let parts = |in_point: &str| vec!["/media/a.mp4|100|1".to_string(), in_point.to_string()];
+        assert_eq!(RenderCache::key(&parts("1.000")), RenderCache::key(&parts("1.000")));
+        assert_ne!(RenderCache::key(&parts("1.000")), RenderCache::key(&parts("1.500")));
+        assert_eq!(RenderCache::key(&parts("1.000")).len(), 32);
+
+        // Joining is unambiguous
+        assert_ne!(
+            RenderCache::key(&["ab".to_string(), "c".to_string()]),
+            RenderCache::key(&["a".to_string(), "bc".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_source_identity_changes_with_file() {
+        let (_, root) = temp_cache(0);
+        let source = root.join("source.mp4");
+        std::fs::write(&source, b"first").unwrap();
+        let before = RenderCache::source_identity(source.to_str().unwrap()).unwrap();
+
+        std::fs::write(&source, b"second version").unwrap();
+        let after = RenderCache::source_identity(source.to_str().unwrap()).unwrap();
+        assert_ne!(before, after, "Size change should change the identity");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_store_fetch_and_lru_eviction() {
+        let (cache, root) = temp_cache(25);
+        let rendered = root.join("rendered.mp4");
+        let fetched = root.join("fetched.mp4");
+
+        assert!(!cache.fetch("a", &fetched));
+
+        // Three 10-byte entries with distinct last-used times; the cap fits two
+        for key in ["a", "b"] {
+            std::fs::write(&rendered, key.repeat(10)).unwrap();
+            cache.store(key, &rendered).unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(cache.fetch("a", &fetched), "a should be cached");
+        assert_eq!(std::fs::read_to_string(&fetched).unwrap(), "a".repeat(10));
+        std::thread::sleep(Duration::from_millis(20));
+
+        // b is now the least recently used entry
+        std::fs::write(&rendered, "c".repeat(10)).unwrap();
+        cache.store("c", &rendered).unwrap();
+        assert!(!cache.fetch("b", &fetched), "b should have been evicted");
+        assert!(cache.fetch("a", &fetched));
+        assert!(cache.fetch("c", &fetched));
+
+        assert_eq!(cache.clear(), Ok(20));
+        assert!(!cache.fetch("a", &fetched));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
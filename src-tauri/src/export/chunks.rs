@@ -0,0 +1,100 @@
+// Chunked encoding: split the final encode at cut points so chunks encode in parallel
+//
+// Chunks are made of whole intermediates, so every chunk boundary is a cut and no
+// frame is shared between chunks. Overlays and redactions are re-timed per chunk, as
+// each chunk's encode starts at t = 0.
+use std::ops::Range;
+
+use super::pipeline::{RedactionData, TextOverlayData};
+use super::range::ExportRange;
+
+/// A run of consecutive intermediates encoded by one FFmpeg process
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub start: f64,             // Position of the chunk in the output (seconds)
+    pub duration: f64,
+    pub files: Range<usize>,    // Indices into the intermediate files
+}
+
+impl Chunk {
+    /// The chunk's window of the output, for re-timing overlays
+    pub fn range(&self) -> ExportRange {
+        ExportRange { start: self.start, end: self.start + self.duration }
+    }
+}
+
+/// Group intermediates (with their durations) into chunks of at least target seconds
+/// The last chunk takes whatever is left
+///
+/// Example: durations [20, 20, 20, 20, 5], target 30 -> [0..2 (40s), 2..4 (40s), 4..5 (5s)]
+pub fn plan_chunks(durations: &[f64], target: f64) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    let mut start = 0.0;
+    let mut length = 0.0;
+
+    for (index, duration) in durations.iter().enumerate() {
+        length += duration;
+        if length >= target || index == durations.len() - 1 {
+            chunks.push(Chunk { start, duration: length, files: first..index + 1 });
+            first = index + 1;
+            start += length;
+            length = 0.0;
+        }
+    }
+    chunks
+}
+
+/// Text overlays visible in a chunk, re-timed to the chunk and cut at its edges
+pub fn text_overlays_for(chunk: &Chunk, overlays: &[TextOverlayData]) -> Vec<TextOverlayData> {
+    chunk.range().text_overlays(overlays)
+}
+
+/// Redactions active in a chunk, re-timed to the chunk and cut at its edges
+pub fn redactions_for(chunk: &Chunk, redactions: &[RedactionData]) -> Vec<RedactionData> {
+    chunk.range().redactions(redactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_chunks_at_cut_points() {
+        let chunks = plan_chunks(&[20.0, 20.0, 20.0, 20.0, 5.0], 30.0);
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk { start: 0.0, duration: 40.0, files: 0..2 },
+                Chunk { start: 40.0, duration: 40.0, files: 2..4 },
+                Chunk { start: 80.0, duration: 5.0, files: 4..5 },
+            ]
+        );
+
+        // A single long intermediate is never split
+        assert_eq!(plan_chunks(&[300.0], 60.0), vec![Chunk { start: 0.0, duration: 300.0, files: 0..1 }]);
+        assert!(plan_chunks(&[], 60.0).is_empty());
+    }
+
+    #[test]
+    fn test_overlays_are_retimed_per_chunk() {
+        let chunk = Chunk { start: 40.0, duration: 40.0, files: 2..4 };
+        let overlay = |start_time: f64, duration: f64| TextOverlayData {
+            id: 1,
+            text: "Caption".to_string(),
+            start_time,
+            duration,
+            x: 50.0,
+            y: 90.0,
+            font_size: 32,
+            font_family: "Arial".to_string(),
+            color: "#FFFFFF".to_string(),
+            animation: "none".to_string(),
+        };
+
+        let overlays = text_overlays_for(&chunk, &[overlay(10.0, 5.0), overlay(35.0, 10.0), overlay(50.0, 40.0)]);
+        let timings: Vec<(f64, f64)> = overlays.iter().map(|o| (o.start_time, o.duration)).collect();
+        // First overlay ends before the chunk; the others are cut at the chunk edges
+        assert_eq!(timings, vec![(0.0, 5.0), (10.0, 30.0)]);
+    }
+}
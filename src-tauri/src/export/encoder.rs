@@ -1,6 +1,15 @@
 // Export encoder settings and resolution options
 use serde::{Deserialize, Serialize};
 
+use super::animation::AnimationSettings;
+use super::audio::AudioSettings;
+use super::layout::LayoutTemplate;
+use super::master::MasterBus;
+use super::range::ExportRange;
+use super::sequence::ImageSequenceSettings;
+use super::stems::StemSettings;
+use super::streaming::StreamingSettings;
+
 /// Resolution options for export
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -31,11 +40,82 @@ impl Resolution {
     }
 }
 
+/// How gaps between clips on the timeline are filled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapFill {
+    #[serde(default = "default_gap_color")]
+    pub color: String,                  // Background color in hex format (#RRGGBB)
+    #[serde(default)]
+    pub slate_path: Option<String>,     // Still image shown during gaps instead of the plain color
+}
+
+fn default_gap_color() -> String {
+    "#000000".to_string()
+}
+
+impl Default for GapFill {
+    fn default() -> Self {
+        Self {
+            color: default_gap_color(),
+            slate_path: None,
+        }
+    }
+}
+
+/// How the timeline is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Renderer {
+    #[default]
+    MultiPass,      // Stream-copy if smart_render allows, else trim clips to intermediates, concatenate, then composite and encode
+    SinglePass,     // One filter_complex over the source files, encoded once (never stream-copied)
+}
+
 /// Export settings for timeline export
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportSettings {
     pub resolution: Resolution,
     pub output_path: String,
+    #[serde(default)]
+    pub layout: LayoutTemplate,     // How overlapping tracks are composited (multi-track only)
+    #[serde(default)]
+    pub master: MasterBus,          // Processing on the final audio mix
+    #[serde(default)]
+    pub gap_fill: GapFill,          // Video shown where the timeline has no clip (audio is silent)
+    #[serde(default = "default_smart_render")]
+    pub smart_render: bool,         // Stream-copy untouched clips instead of re-encoding them (multi-pass renderer only)
+    #[serde(default)]
+    pub renderer: Renderer,
+    #[serde(default)]
+    pub parallelism: Option<usize>, // Concurrent FFmpeg jobs when trimming clips (None = based on CPU count)
+    #[serde(default = "default_render_cache")]
+    pub render_cache: bool,         // Reuse trimmed segments rendered by earlier exports
+    #[serde(default)]
+    pub chunk_duration: Option<f64>, // Encode in parallel chunks of about this many seconds (None = one encode); multi-pass single-track exports without transitions
+    #[serde(default)]
+    pub dry_run: bool,              // Return the render plan instead of running FFmpeg
+    #[serde(default)]
+    pub script_path: Option<String>, // With dry_run, also write the plan here as a shell script
+    #[serde(default)]
+    pub range: Option<ExportRange>, // Export only this window of the timeline (None = everything)
+    #[serde(default)]
+    pub animation: Option<AnimationSettings>, // Write a GIF or animated WebP instead of a video
+    #[serde(default)]
+    pub audio_only: Option<AudioSettings>, // Write only the mixed audio, without encoding video
+    #[serde(default)]
+    pub stems: Option<StemSettings>, // Also export the audio of each track or tag group
+    #[serde(default)]
+    pub image_sequence: Option<ImageSequenceSettings>, // Write numbered images into the output_path directory
+    #[serde(default)]
+    pub streaming: Option<StreamingSettings>, // Write an HLS/DASH bitrate ladder into the output_path directory
+}
+
+fn default_smart_render() -> bool {
+    true
+}
+
+fn default_render_cache() -> bool {
+    true
 }
 
 impl ExportSettings {
@@ -43,6 +123,22 @@ impl ExportSettings {
         Self {
             resolution,
             output_path,
+            layout: LayoutTemplate::default(),
+            master: MasterBus::default(),
+            gap_fill: GapFill::default(),
+            smart_render: default_smart_render(),
+            renderer: Renderer::default(),
+            parallelism: None,
+            render_cache: default_render_cache(),
+            chunk_duration: None,
+            dry_run: false,
+            script_path: None,
+            range: None,
+            animation: None,
+            audio_only: None,
+            stems: None,
+            image_sequence: None,
+            streaming: None,
         }
     }
 }
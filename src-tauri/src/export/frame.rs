@@ -0,0 +1,124 @@
+// Still frames: the fully composited timeline at one timestamp, as an image
+//
+// The timeline is cut to a short window starting at the timestamp (see ExportRange) and
+// rendered with the single-pass graph, so base clips, overlays, redactions and captions
+// look exactly as in an export. Only the first frame is encoded.
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::capabilities::Feature;
+use super::encoder::ExportSettings;
+use super::graph;
+use super::pipeline::{ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData};
+use super::range::ExportRange;
+use super::tracks::{self, TrackData};
+
+/// Length of timeline rendered for a frame; only its first frame is kept
+const FRAME_WINDOW: f64 = 0.1;
+
+/// Image format of an exported frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl FrameFormat {
+    fn encoder(&self) -> &'static str {
+        match self {
+            FrameFormat::Png => "png",
+            FrameFormat::Jpeg => "mjpeg",
+            FrameFormat::Webp => "libwebp",
+        }
+    }
+
+    /// Encoder arguments for one high quality image
+    fn encoder_args(&self) -> Vec<&'static str> {
+        match self {
+            FrameFormat::Png => vec!["-c:v", "png"],
+            // Full-range YUV is what JPEG viewers expect; q 2 is near the best quality
+            FrameFormat::Jpeg => vec!["-c:v", "mjpeg", "-pix_fmt", "yuvj420p", "-q:v", "2"],
+            FrameFormat::Webp => vec!["-c:v", "libwebp", "-quality", "90"],
+        }
+    }
+}
+
+impl ExportPipeline {
+    /// Render the composited frame at a timeline timestamp to an image
+    ///
+    /// settings supply the export resolution, layout and gap fill; their output path, range
+    /// and encoding options don't apply to frames.
+    #[allow(clippy::too_many_arguments)]
+    pub fn export_frame(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: &ExportSettings,
+        time: f64,
+        output_path: &str,
+        format: FrameFormat,
+    ) -> Result<String, String> {
+        eprintln!("🖼️  Exporting {:?} frame at {:.3}s to {}", format, time, output_path);
+
+        let window = ExportRange { start: time, end: time + FRAME_WINDOW };
+        window.validate()?;
+        tracks::validate(&tracks)?;
+
+        let clips = self.apply_track_settings(window.clips(&clips), &tracks);
+        if clips.is_empty() {
+            return Err(format!("No clip at {:.3}s", time));
+        }
+        let transitions = window.transitions(&transitions, &clips);
+        let text_overlays = window.text_overlays(&text_overlays);
+        let redactions = window.redactions(&redactions);
+        self.validate_timeline(&clips, &transitions, &text_overlays, &redactions)?;
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        let mut required = vec![(Feature::Encoder(format.encoder()), "this frame format")];
+        if settings.resolution.scale_filter().is_some() {
+            required.push((Feature::Filter("scale"), "the export resolution"));
+        }
+        let has_temporal_overlap = self.has_temporal_overlap(&clips);
+        required.extend(self.timeline_features(&clips, &transitions, &text_overlays, &redactions, has_temporal_overlap));
+        ffmpeg.require(&required)?;
+
+        let sources = graph::probe_sources(&ffmpeg, &clips)?;
+        let graph = self.build_single_pass_graph(&clips, &transitions, &text_overlays, &redactions, &tracks, settings, &sources)?;
+
+        // Only the picture is wanted; the mix is consumed by a sink so no pad is left unconnected
+        let mut args: Vec<String> = graph.inputs.iter().flatten().cloned().collect();
+        args.push("-filter_complex".to_string());
+        args.push(format!("{};{}anullsink", graph.filter_complex, graph.audio_label));
+        args.push("-map".to_string());
+        args.push(graph.video_label.clone());
+        args.extend(format.encoder_args().into_iter().map(|s| s.to_string()));
+        args.extend(["-frames:v", "1", "-f", "image2", "-update", "1", "-y"].iter().map(|s| s.to_string()));
+        args.push(output_path.to_string());
+
+        eprintln!("📊 FRAME: FFmpeg arguments: {:?}", args);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+
+        Ok(output_path.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_formats() {
+        let format: FrameFormat = serde_json::from_str("\"webp\"").unwrap();
+        assert_eq!(format, FrameFormat::Webp);
+        assert_eq!(format.encoder(), "libwebp");
+        assert!(FrameFormat::Jpeg.encoder_args().contains(&"yuvj420p"));
+    }
+}
@@ -0,0 +1,648 @@
+// Single-pass rendering: the whole timeline as one filter graph, encoded once
+//
+// The multi-pass renderer writes every trimmed clip and gap to an intermediate file,
+// concatenates them, and composites overlays in a further encode. Here each clip is an
+// input of one filter_complex instead: trim/atrim per input, concat (or xfade at
+// transitions) for the base track, overlay for the other tracks, then redactions,
+// captions and the master bus.
+use std::collections::{HashMap, HashSet};
+
+use crate::ffmpeg::metadata::VideoMetadata;
+use crate::ffmpeg::FFmpegWrapper;
+
+use super::encoder::ExportSettings;
+use super::pipeline::{ffmpeg_color, ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData, MIN_GAP};
+use super::tracks::TrackData;
+use super::transitions::{self, TrackSegment};
+
+/// Frame size and rate used when no clip has video
+const DEFAULT_FRAME: (u32, u32, f64) = (1280, 720, 30.0);
+
+/// Stream parameters of a source file that the graph depends on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceInfo {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub has_audio: bool,
+}
+
+impl From<&VideoMetadata> for SourceInfo {
+    fn from(metadata: &VideoMetadata) -> Self {
+        Self {
+            width: metadata.width,
+            height: metadata.height,
+            fps: metadata.fps,
+            has_audio: metadata.audio_codec.is_some(),
+        }
+    }
+}
+
+/// Probe every distinct source of the clips, keyed by path
+pub fn probe_sources(ffmpeg: &FFmpegWrapper, clips: &[ClipData]) -> Result<HashMap<String, SourceInfo>, String> {
+    let mut sources = HashMap::new();
+    for clip in clips {
+        if !sources.contains_key(&clip.path) {
+            let metadata = ffmpeg.probe(&clip.path)?;
+            sources.insert(clip.path.clone(), SourceInfo::from(&metadata));
+        }
+    }
+    Ok(sources)
+}
+
+/// A complete single-pass render: the inputs and the filter graph over them
+#[derive(Debug, Clone)]
+pub struct RenderGraph {
+    pub inputs: Vec<Vec<String>>,   // Arguments of each input in input order, each ending with -i <path>
+    pub filter_complex: String,
+    pub video_label: String,        // Graph pad carrying the final video
+    pub audio_label: String,        // Graph pad carrying the final mix
+    pub duration: f64,              // Output duration in seconds
+}
+
+impl RenderGraph {
+    /// Input, filter and mapping arguments; the caller adds encoder options and the output
+    pub fn args(&self) -> Vec<String> {
+        let mut args: Vec<String> = self.inputs.iter().flatten().cloned().collect();
+        args.push("-filter_complex".to_string());
+        args.push(self.filter_complex.clone());
+        args.push("-map".to_string());
+        args.push(self.video_label.clone());
+        args.push("-map".to_string());
+        args.push(self.audio_label.clone());
+        args
+    }
+}
+
+/// Inputs and filter parts collected while building a graph
+#[derive(Default)]
+struct GraphBuilder {
+    inputs: Vec<Vec<String>>,
+    parts: Vec<String>,
+}
+
+impl GraphBuilder {
+    /// Add an input and return its index
+    fn input(&mut self, args: Vec<String>) -> usize {
+        self.inputs.push(args);
+        self.inputs.len() - 1
+    }
+
+    /// Add a clip's source range as an input (seeking on input keeps decoding short)
+    fn clip_input(&mut self, clip: &ClipData) -> usize {
+        self.input(vec![
+            "-ss".to_string(),
+            format!("{:.3}", clip.in_point),
+            "-t".to_string(),
+            format!("{:.3}", clip.out_point - clip.in_point),
+            "-i".to_string(),
+            clip.path.clone(),
+        ])
+    }
+}
+
+/// A piece of the base track
+enum Segment<'a> {
+    Gap(f64),               // Gap fill of this duration
+    Clip(&'a ClipData),
+}
+
+/// Source audio trimmed to the clip and brought to the common mix format
+/// apad + atrim pad sources whose audio is shorter than their video
+fn clip_audio_chain(input_index: usize, duration: f64, filters: &[String]) -> String {
+    let mut chain = vec![
+        format!("[{}:a]aresample=48000", input_index),
+        "aformat=channel_layouts=stereo".to_string(),
+        "apad".to_string(),
+        format!("atrim=duration={:.3}", duration),
+        "asetpts=PTS-STARTPTS".to_string(),
+    ];
+    chain.extend(filters.iter().cloned());
+    chain.join(",")
+}
+
+/// Silence of the given length in the common mix format
+fn silence(duration: f64) -> String {
+    format!("anullsrc=r=48000:cl=stereo,atrim=duration={:.3}", duration)
+}
+
+impl ExportPipeline {
+    /// Build the single-pass render of a timeline
+    ///
+    /// clips are prepared as in export_timeline (track visibility and audibility already
+    /// folded into video_enabled / audio_enabled). sources holds the probed parameters of
+    /// every clip's path.
+    ///
+    /// Sequential timelines put every clip on the base track; when clips on different
+    /// tracks overlap, track 0 is the base and the other tracks are composited on top with
+    /// the layout template, as in the multi-pass multitrack export. Each base clip and gap
+    /// becomes a segment scaled and padded to the canvas, and the segments are joined with
+    /// xfade/acrossfade at transitions and concat elsewhere:
+    /// "[0:v]trim=duration=2.000,setpts=PTS-STARTPTS,scale=1920:1080:force_original_aspect_ratio=decrease,pad=...,setsar=1,fps=30,format=yuv420p[v0];[0:a]aresample=48000,...[a0];[v0][a0]concat=n=1:v=1:a=1[vbase][abase]"
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_single_pass_graph(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+        sources: &HashMap<String, SourceInfo>,
+    ) -> Result<RenderGraph, String> {
+        if clips.is_empty() {
+            return Err("No clips to export".to_string());
+        }
+        let source = |clip: &ClipData| {
+            sources.get(&clip.path)
+                .copied()
+                .ok_or_else(|| format!("Source {} was not probed", clip.path))
+        };
+
+        // Split into the base track and overlays the same way export_timeline routes
+        let (mut base_clips, mut overlay_clips): (Vec<ClipData>, Vec<ClipData>) = if self.has_temporal_overlap(clips) {
+            clips.iter().cloned().partition(|c| c.track == 0)
+        } else {
+            (clips.to_vec(), Vec::new())
+        };
+        base_clips.sort_by(|a, b| {
+            a.start_time.partial_cmp(&b.start_time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        overlay_clips.sort_by(|a, b| {
+            a.track.cmp(&b.track).then(
+                a.start_time.partial_cmp(&b.start_time)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            )
+        });
+        if base_clips.is_empty() {
+            return Err("Multi-track export requires at least one clip on track 0 (base)".to_string());
+        }
+
+        // Only transitions between base clips are rendered
+        let base_ids: HashSet<u32> = base_clips.iter().map(|c| c.id).collect();
+        let base_transitions: Vec<TransitionData> = transitions.iter()
+            .filter(|t| base_ids.contains(&t.clip_id_before) && base_ids.contains(&t.clip_id_after))
+            .cloned()
+            .collect();
+
+        let positions = self.output_positions(&base_clips, &base_transitions);
+        let base_end = match (positions.last(), base_clips.last()) {
+            (Some(position), Some(clip)) => position + (clip.out_point - clip.in_point),
+            _ => 0.0,
+        };
+        let timeline_end = overlay_clips.iter()
+            .map(|c| c.start_time + (c.out_point - c.in_point))
+            .fold(base_end, f64::max);
+
+        // Canvas: export resolution, or the first clip with video (as the concat output would be)
+        let (mut width, mut height, mut fps) = DEFAULT_FRAME;
+        if let Some(clip) = base_clips.iter().chain(&overlay_clips).find(|c| c.video_enabled) {
+            let info = source(clip)?;
+            (width, height, fps) = (info.width, info.height, info.fps);
+        }
+        if let Some((w, h)) = settings.resolution.dimensions() {
+            (width, height) = (w, h);
+        }
+
+        // Transparent background (image sequences with alpha): letterboxing, gaps and
+        // audio-only clips are see-through instead of black or the gap fill
+        let transparent = settings.image_sequence.as_ref().is_some_and(|s| s.alpha);
+        let (background, pixel_format) = if transparent { ("black@0", "yuva420p") } else { ("black", "yuv420p") };
+        let fit = format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color={bg},setsar=1,fps={fps},format={pf}",
+            w = width, h = height, bg = background, fps = fps, pf = pixel_format
+        );
+
+        let mut graph = GraphBuilder::default();
+
+        // J/L cuts and video-only clips: the base audio is mixed separately from the cuts
+        let separate_audio = self.needs_separate_audio(&base_clips, &base_transitions);
+
+        // Base track segments: gaps and clips in timeline order
+        let mut segments = Vec::new();
+        let mut previous_end = 0.0;
+        for (clip, &position) in base_clips.iter().zip(&positions) {
+            if clip.out_point - clip.in_point <= 0.0 {
+                return Err(format!("Clip {} has invalid duration", clip.id));
+            }
+            if position - previous_end > MIN_GAP {
+                segments.push(Segment::Gap(position - previous_end));
+            }
+            segments.push(Segment::Clip(clip));
+            previous_end = position + (clip.out_point - clip.in_point);
+        }
+        if timeline_end - base_end > MIN_GAP {
+            segments.push(Segment::Gap(timeline_end - base_end));
+        }
+
+        let mut track_segments = Vec::new();
+        for (n, segment) in segments.iter().enumerate() {
+            let (video, audio) = match *segment {
+                Segment::Gap(duration) => {
+                    let color = if transparent { background.to_string() } else { ffmpeg_color(&settings.gap_fill.color)? };
+                    let video = match &settings.gap_fill.slate_path {
+                        Some(slate) => {
+                            let input_index = graph.input(vec![
+                                "-loop".to_string(),
+                                "1".to_string(),
+                                "-framerate".to_string(),
+                                fps.to_string(),
+                                "-t".to_string(),
+                                format!("{:.3}", duration),
+                                "-i".to_string(),
+                                slate.clone(),
+                            ]);
+                            format!(
+                                "[{i}:v]scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color={c},setsar=1,fps={fps},format=yuv420p",
+                                i = input_index, w = width, h = height, c = color, fps = fps
+                            )
+                        }
+                        None => format!(
+                            "color=c={}:s={}x{}:r={}:d={:.3},setsar=1,format={}",
+                            color, width, height, fps, duration, pixel_format
+                        ),
+                    };
+                    (video, silence(duration))
+                }
+                Segment::Clip(clip) => {
+                    let duration = clip.out_point - clip.in_point;
+                    let info = source(clip)?;
+                    let audio_from_source = !separate_audio && clip.audio_enabled && info.has_audio;
+                    let input_index = if clip.video_enabled || audio_from_source {
+                        Some(graph.clip_input(clip))
+                    } else {
+                        None
+                    };
+
+                    let video = match input_index {
+                        Some(i) if clip.video_enabled => format!(
+                            "[{}:v]trim=duration={:.3},setpts=PTS-STARTPTS{},{}",
+                            i, duration, self.zoom_suffix(clip, &info), fit
+                        ),
+                        // Audio-only clip: black frames under its audio
+                        _ => format!(
+                            "color=c={}:s={}x{}:r={}:d={:.3},setsar=1,format={}",
+                            background, width, height, fps, duration, pixel_format
+                        ),
+                    };
+                    let audio = match input_index {
+                        Some(i) if audio_from_source => {
+                            clip_audio_chain(i, duration, &self.build_clip_audio_filters(clip, duration, tracks))
+                        }
+                        // Source without an audio stream (e.g. screen recording)
+                        _ => silence(duration),
+                    };
+                    (video, audio)
+                }
+            };
+
+            graph.parts.push(format!("{}[v{}]", video, n));
+            if !separate_audio {
+                graph.parts.push(format!("{}[a{}]", audio, n));
+            }
+            let (duration, clip_id) = match *segment {
+                Segment::Gap(duration) => (duration, None),
+                Segment::Clip(clip) => (clip.out_point - clip.in_point, Some(clip.id)),
+            };
+            track_segments.push(TrackSegment {
+                video: format!("[v{}]", n),
+                audio: if separate_audio { None } else { Some(format!("[a{}]", n)) },
+                duration,
+                clip_id,
+            });
+        }
+        graph.parts.extend(transitions::join_segments(&track_segments, &base_transitions, "[vbase]", "[abase]")?);
+
+        if separate_audio {
+            let has_audio = |clip: &ClipData| {
+                sources.get(&clip.path).map(|s| s.has_audio).unwrap_or(true)
+            };
+            let (inputs, parts) = self.build_split_audio_graph(
+                &base_clips, &base_transitions, tracks, &has_audio, graph.inputs.len(), timeline_end, "[abase]"
+            )?;
+            graph.inputs.extend(inputs);
+            graph.parts.extend(parts);
+        }
+
+        let mut video_label = "[vbase]".to_string();
+        let mut audio_label = "[abase]".to_string();
+
+        // Overlay tracks: trimmed here, placed and mixed by the multitrack overlay builder
+        if !overlay_clips.is_empty() {
+            let mut overlay_labels = Vec::new();
+            for (j, clip) in overlay_clips.iter().enumerate() {
+                let duration = clip.out_point - clip.in_point;
+                let info = source(clip)?;
+                let input_index = graph.clip_input(clip);
+
+                if clip.video_enabled {
+                    graph.parts.push(format!(
+                        "[{}:v]trim=duration={:.3},setpts=PTS-STARTPTS{}[ov{}]",
+                        input_index, duration, self.zoom_suffix(clip, &info), j
+                    ));
+                }
+                if clip.audio_enabled {
+                    let audio = if info.has_audio {
+                        clip_audio_chain(input_index, duration, &self.build_clip_audio_filters(clip, duration, tracks))
+                    } else {
+                        silence(duration)
+                    };
+                    graph.parts.push(format!("{}[oa{}]", audio, j));
+                }
+                overlay_labels.push((format!("[ov{}]", j), format!("[oa{}]", j)));
+            }
+
+            let (video_filter, audio_filter) = self.build_overlay_and_audio_filter(
+                ("[vbase]", "[abase]"), &overlay_labels, &overlay_clips, settings.layout, (width, height)
+            )?;
+            graph.parts.push(video_filter);
+            graph.parts.push(audio_filter);
+            video_label = "[out]".to_string();
+            audio_label = "[aout]".to_string();
+        }
+
+        // Redactions cover composited content; captions are drawn last so they are never blurred
+        if !redactions.is_empty() {
+            graph.parts.extend(self.build_redaction_filter(redactions, &video_label, "[vredacted]")?);
+            video_label = "[vredacted]".to_string();
+        }
+        if !text_overlays.is_empty() {
+            let text_chain = text_overlays.iter()
+                .map(|overlay| self.build_drawtext_filter(overlay))
+                .collect::<Result<Vec<_>, _>>()?;
+            graph.parts.push(format!("{}{}[vtext]", video_label, text_chain.join(",")));
+            video_label = "[vtext]".to_string();
+        }
+
+        if let Some(chain) = settings.master.filter_chain() {
+            graph.parts.push(format!("{}{}[amaster]", audio_label, chain));
+            audio_label = "[amaster]".to_string();
+        }
+
+        Ok(RenderGraph {
+            inputs: graph.inputs,
+            filter_complex: graph.parts.join(";"),
+            video_label,
+            audio_label,
+            duration: timeline_end,
+        })
+    }
+
+    /// Zoom regions as a filter to append to a clip's video chain ("" without zoom)
+    fn zoom_suffix(&self, clip: &ClipData, info: &SourceInfo) -> String {
+        if clip.zoom_regions.is_empty() {
+            String::new()
+        } else {
+            format!(",{}", self.build_zoompan_filter(&clip.zoom_regions, info.width, info.height, info.fps))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use crate::export::encoder::{Renderer, Resolution};
+    use crate::export::pipeline::{RedactionMode, ZoomRegionData, TEST_VIDEO};
+    use crate::export::transitions::TransitionOptions;
+    use crate::export::LayoutTemplate;
+    use crate::ffmpeg::FFmpegWrapper;
+
+    // Graphs are built without running FFmpeg, so these mirror the export_test timelines
+    // without probing the fixture or needing the binaries
+
+    const SCREEN: &str = "/media/screen.mp4";
+
+    fn pipeline() -> ExportPipeline {
+        ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())))
+    }
+
+    fn sources() -> HashMap<String, SourceInfo> {
+        let mut sources = HashMap::new();
+        sources.insert(TEST_VIDEO.to_string(), SourceInfo { width: 1920, height: 1080, fps: 30.0, has_audio: true });
+        sources.insert(SCREEN.to_string(), SourceInfo { width: 2560, height: 1440, fps: 60.0, has_audio: false });
+        sources
+    }
+
+    fn settings(resolution: Resolution) -> ExportSettings {
+        ExportSettings {
+            renderer: Renderer::SinglePass,
+            ..ExportSettings::new(resolution, "/tmp/out.mp4".to_string())
+        }
+    }
+
+    fn build(clips: &[ClipData], settings: &ExportSettings) -> RenderGraph {
+        pipeline()
+            .build_single_pass_graph(clips, &[], &[], &[], &[], settings, &sources())
+            .expect("Graph should build")
+    }
+
+    #[test]
+    fn test_single_and_trimmed_clips() {
+        let graph = build(&[ClipData::fixture(1, 0.0, 0.5, 1.5)], &settings(Resolution::Source));
+
+        assert_eq!(graph.inputs, vec![vec!["-ss", "0.500", "-t", "1.000", "-i", TEST_VIDEO]]);
+        assert_eq!(
+            graph.filter_complex,
+            "[0:v]trim=duration=1.000,setpts=PTS-STARTPTS,scale=1920:1080:force_original_aspect_ratio=decrease,pad=1920:1080:(ow-iw)/2:(oh-ih)/2:color=black,setsar=1,fps=30,format=yuv420p[v0];\
+             [0:a]aresample=48000,aformat=channel_layouts=stereo,apad,atrim=duration=1.000,asetpts=PTS-STARTPTS[a0];\
+             [v0][a0]concat=n=1:v=1:a=1[vbase][abase]"
+        );
+        assert_eq!((graph.video_label.as_str(), graph.audio_label.as_str()), ("[vbase]", "[abase]"));
+        assert_eq!(graph.duration, 1.0);
+        assert_eq!(graph.args().iter().filter(|a| *a == "-map").count(), 2);
+    }
+
+    #[test]
+    fn test_multiple_clips_scaled_to_720p() {
+        let graph = build(
+            &[ClipData::fixture(1, 0.0, 0.0, 1.0), ClipData::fixture(2, 1.0, 0.5, 2.0)],
+            &settings(Resolution::HD720),
+        );
+
+        assert_eq!(graph.inputs.len(), 2);
+        assert!(graph.filter_complex.contains("[1:v]trim=duration=1.500,setpts=PTS-STARTPTS,scale=1280:720:"));
+        assert!(graph.filter_complex.ends_with("[v0][a0][v1][a1]concat=n=2:v=1:a=1[vbase][abase]"));
+        assert_eq!(graph.duration, 2.5);
+    }
+
+    #[test]
+    fn test_transitions_overlap_their_clips() {
+        let clips = [ClipData::fixture(1, 0.0, 0.0, 1.0), ClipData::fixture(2, 1.0, 0.0, 1.0), ClipData::fixture(3, 3.0, 0.0, 1.0)];
+        let slide = TransitionData {
+            id: 1,
+            clip_id_before: 1,
+            clip_id_after: 2,
+            transition_type: "slide".to_string(),
+            duration: 0.5,
+            options: TransitionOptions { direction: Some("up".to_string()), ..Default::default() },
+            audio_offset: 0.0,
+        };
+        let graph = pipeline()
+            .build_single_pass_graph(&clips, &[slide], &[], &[], &[], &settings(Resolution::Source), &sources())
+            .expect("Graph should build");
+
+        // Clip 2 and everything after it play half a second early; the gap keeps its length
+        assert!(graph.filter_complex.contains("[v0][v1]xfade=transition=slideup:duration=0.500:offset=0.500[vx1]"));
+        assert!(graph.filter_complex.contains("[a0][a1]acrossfade=d=0.500[ax1]"));
+        assert!(graph.filter_complex.contains("color=c=0x000000:s=1920x1080:r=30:d=1.000,setsar=1,format=yuv420p[v2]"));
+        assert!(graph.filter_complex.ends_with("[vx1][ax1][v2][a2][v3][a3]concat=n=3:v=1:a=1[vbase][abase]"));
+        assert_eq!(graph.duration, 3.5);
+    }
+
+    #[test]
+    fn test_empty_timeline_is_rejected() {
+        let result = pipeline().build_single_pass_graph(&[], &[], &[], &[], &[], &settings(Resolution::Source), &sources());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_multitrack_layouts() {
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData { track: 1, ..ClipData::fixture(2, 0.5, 0.0, 1.0) }];
+
+        // Picture-in-picture: overlay trimmed, shifted to 0.5s and mixed with the base audio
+        let graph = build(&clips, &settings(Resolution::Source));
+        assert!(graph.filter_complex.contains("[1:v]trim=duration=1.000,setpts=PTS-STARTPTS[ov0]"));
+        assert!(graph.filter_complex.contains("[ov0]setpts=PTS-STARTPTS+0.500/TB,scale=iw*0.25:-1[scaled1]"));
+        assert!(graph.filter_complex.contains("[vbase][scaled1]overlay=W-w-20:H-h-20:enable='between(t,0.500,1.500)'[out]"));
+        assert!(graph.filter_complex.contains("[oa0]adelay=500:all=1[adelayed1];[abase][adelayed1]amix=inputs=2:duration=first:normalize=0[aout]"));
+        assert_eq!((graph.video_label.as_str(), graph.audio_label.as_str()), ("[out]", "[aout]"));
+
+        // Side by side on the source canvas
+        let mut side_by_side = settings(Resolution::Source);
+        side_by_side.layout = LayoutTemplate::SideBySide;
+        let graph = build(&clips, &side_by_side);
+        assert!(graph.filter_complex.contains("[vbase]scale=960:1080:"));
+        assert!(graph.filter_complex.contains("[base][scaled1]overlay=960:0:"));
+    }
+
+    #[test]
+    fn test_overlay_extends_timeline() {
+        // Base is 1s; overlays on tracks 1 and 2 run to 3s, so the base gets a trailing gap
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 1.0),
+            ClipData { track: 2, ..ClipData::fixture(2, 0.5, 0.0, 2.0) },
+            ClipData { track: 1, ..ClipData::fixture(3, 1.0, 0.0, 2.0) },
+        ];
+        let graph = build(&clips, &settings(Resolution::Source));
+
+        assert_eq!(graph.duration, 3.0);
+        assert!(graph.filter_complex.contains("color=c=0x000000:s=1920x1080:r=30:d=2.000,setsar=1,format=yuv420p[v1]"));
+        assert!(graph.filter_complex.contains("concat=n=2:v=1:a=1[vbase][abase]"));
+        // Track 1 (input 1) composites below track 2 (input 2)
+        assert!(graph.filter_complex.contains("[vbase][scaled1]overlay=W-w-20:H-h-20:enable='between(t,1.000,3.000)'[temp1]"));
+        assert!(graph.filter_complex.contains("[temp1][scaled2]overlay=W-w-20:H-h-20:enable='between(t,0.500,2.500)'[out]"));
+    }
+
+    #[test]
+    fn test_timeline_gaps_and_silent_sources() {
+        let mut settings = settings(Resolution::Source);
+        settings.gap_fill.color = "#202020".to_string();
+        let mut screen = ClipData::fixture(2, 2.0, 0.0, 1.0);
+        screen.path = SCREEN.to_string();
+        let graph = build(&[ClipData::fixture(1, 0.5, 0.0, 1.0), screen], &settings);
+
+        // Leading gap, clip, gap, screen recording (scaled down to the first clip's canvas)
+        assert!(graph.filter_complex.starts_with("color=c=0x202020:s=1920x1080:r=30:d=0.500,setsar=1,format=yuv420p[v0];anullsrc=r=48000:cl=stereo,atrim=duration=0.500[a0]"));
+        assert!(graph.filter_complex.contains("[1:v]trim=duration=1.000,setpts=PTS-STARTPTS,scale=1920:1080:"));
+        assert!(graph.filter_complex.contains("anullsrc=r=48000:cl=stereo,atrim=duration=1.000[a3]"));
+        assert!(graph.filter_complex.ends_with("concat=n=4:v=1:a=1[vbase][abase]"));
+        assert_eq!(graph.duration, 3.0);
+
+        // Image sequences with alpha: gaps and letterboxing are transparent, not the gap fill
+        settings.image_sequence = serde_json::from_str(r#"{"format":"png","alpha":true}"#).unwrap();
+        let graph = build(&[ClipData::fixture(1, 0.5, 0.0, 1.0)], &settings);
+        assert!(graph.filter_complex.starts_with("color=c=black@0:s=1920x1080:r=30:d=0.500,setsar=1,format=yuva420p[v0]"));
+        assert!(graph.filter_complex.contains(":color=black@0,setsar=1,fps=30,format=yuva420p[v1]"));
+    }
+
+    #[test]
+    fn test_split_audio_edits() {
+        // J/L cut: video concat without audio, audio mixed from its own inputs
+        let mut second = ClipData::fixture(2, 1.0, 1.0, 2.0);
+        second.audio_lead = 0.5;
+        let graph = build(&[ClipData::fixture(1, 0.0, 0.0, 1.0), second], &settings(Resolution::Source));
+
+        assert_eq!(graph.inputs.len(), 4);
+        assert!(graph.filter_complex.contains("[v0][v1]concat=n=2:v=1:a=0[vbase]"));
+        assert!(graph.filter_complex.contains("[3:a]aresample=48000,aformat=channel_layouts=stereo,apad,atrim=duration=1.500,adelay=500:all=1[sa1]"));
+        assert!(graph.filter_complex.ends_with("[sa0][sa1]amix=inputs=2:duration=longest:normalize=0,apad,atrim=duration=2.000[abase]"));
+
+        // Unlinked: a video-only clip takes no audio input, an audio-only clip gets black frames
+        let mut video_only = ClipData::fixture(1, 0.0, 0.0, 1.0);
+        video_only.audio_enabled = false;
+        let mut audio_only = ClipData::fixture(2, 1.0, 1.0, 2.0);
+        audio_only.video_enabled = false;
+        let graph = build(&[video_only, audio_only], &settings(Resolution::Source));
+
+        assert_eq!(graph.inputs.len(), 2);
+        assert!(graph.filter_complex.contains("color=c=black:s=1920x1080:r=30:d=1.000,setsar=1,format=yuv420p[v1]"));
+        assert!(graph.filter_complex.contains("[1:a]aresample=48000,aformat=channel_layouts=stereo,apad,atrim=duration=1.000,adelay=1000:all=1[sa0]"));
+    }
+
+    #[test]
+    fn test_clip_effects_and_finishing() {
+        let mut zoomed = ClipData::fixture(1, 0.0, 0.0, 2.0);
+        zoomed.volume = 50;
+        zoomed.zoom_regions = vec![ZoomRegionData {
+            start_time: 0.5,
+            duration: 1.0,
+            x: 0.0,
+            y: 0.0,
+            width: 50.0,
+            height: 50.0,
+            ease_in: 0.0,
+            ease_out: 0.0,
+        }];
+        let tracks = vec![TrackData {
+            index: 0,
+            muted: false,
+            solo: false,
+            hidden: false,
+            locked: false,
+            gain_db: -6.0,
+            pan: 0.0,
+            tag: None,
+        }];
+        let redactions = vec![RedactionData {
+            id: 1,
+            start_time: 0.0,
+            duration: 1.0,
+            x: 10.0,
+            y: 10.0,
+            width: 20.0,
+            height: 20.0,
+            mode: RedactionMode::Box,
+            strength: 10,
+            color: "#000000".to_string(),
+        }];
+        let text = vec![TextOverlayData {
+            id: 1,
+            text: "Title".to_string(),
+            start_time: 0.0,
+            duration: 1.0,
+            x: 50.0,
+            y: 10.0,
+            font_size: 48,
+            font_family: "Arial".to_string(),
+            color: "#FFFFFF".to_string(),
+            animation: "none".to_string(),
+        }];
+        let mut settings = settings(Resolution::Source);
+        settings.master.limiter_ceiling_db = Some(-1.0);
+
+        let graph = pipeline()
+            .build_single_pass_graph(&[zoomed], &[], &text, &redactions, &tracks, &settings, &sources())
+            .expect("Graph should build");
+
+        assert!(graph.filter_complex.contains("setpts=PTS-STARTPTS,zoompan=z='1+"));
+        assert!(graph.filter_complex.contains("asetpts=PTS-STARTPTS,volume=0.50,volume=-6.00dB[a0]"));
+        assert!(graph.filter_complex.contains("[vbase]drawbox="));
+        // Captions are drawn after redactions; the master bus runs on the final mix
+        assert!(graph.filter_complex.contains("[vredacted]drawtext=text='Title'"));
+        assert!(graph.filter_complex.ends_with("[abase]alimiter=limit=-1.0dB:attack=5:release=50:level=0[amaster]"));
+        assert_eq!((graph.video_label.as_str(), graph.audio_label.as_str()), ("[vtext]", "[amaster]"));
+    }
+}
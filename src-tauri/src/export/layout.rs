@@ -0,0 +1,98 @@
+// Layout templates for placing concurrent tracks in multi-track export
+use serde::{Deserialize, Serialize};
+
+/// Named layout used to composite overlapping tracks
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LayoutTemplate {
+    #[default]
+    Pip,            // Track 0 full frame, other tracks at 25% width in the bottom-right corner
+    SideBySide,     // 50/50 split, first track on the left
+    Grid,           // 2x2 grid, tracks fill left-to-right, top-to-bottom
+    Presenter,      // First track takes 70% on the left, second track the remaining 30%
+}
+
+/// Pixel rectangle on the output canvas where a track is placed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutSlot {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl LayoutTemplate {
+    /// Slot rectangles as fractions of the canvas (x, y, width, height)
+    /// Empty for picture-in-picture, where overlays keep their own corner placement
+    fn regions(&self) -> &'static [(f64, f64, f64, f64)] {
+        match self {
+            LayoutTemplate::Pip => &[],
+            LayoutTemplate::SideBySide => &[(0.0, 0.0, 0.5, 1.0), (0.5, 0.0, 0.5, 1.0)],
+            LayoutTemplate::Grid => &[
+                (0.0, 0.0, 0.5, 0.5),
+                (0.5, 0.0, 0.5, 0.5),
+                (0.0, 0.5, 0.5, 0.5),
+                (0.5, 0.5, 0.5, 0.5),
+            ],
+            LayoutTemplate::Presenter => &[(0.0, 0.0, 0.7, 1.0), (0.7, 0.0, 0.3, 1.0)],
+        }
+    }
+
+    /// Whether tracks are tiled into fixed slots (anything but picture-in-picture)
+    pub fn is_tiled(&self) -> bool {
+        !self.regions().is_empty()
+    }
+
+    /// Maximum number of tracks this layout can place
+    /// Returns None for picture-in-picture (any number of overlays)
+    pub fn max_tracks(&self) -> Option<usize> {
+        if self.is_tiled() {
+            Some(self.regions().len())
+        } else {
+            None
+        }
+    }
+
+    /// Get the pixel slot for the Nth track (in track order) on a canvas
+    /// Sizes and positions are rounded down to even numbers for yuv420p
+    pub fn slot(&self, index: usize, canvas_width: u32, canvas_height: u32) -> Option<LayoutSlot> {
+        let even = |value: f64| (value.round() as u32) & !1;
+        self.regions().get(index).map(|&(x, y, w, h)| LayoutSlot {
+            x: even(x * canvas_width as f64),
+            y: even(y * canvas_height as f64),
+            width: even(w * canvas_width as f64),
+            height: even(h * canvas_height as f64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_slots() {
+        let side_by_side = LayoutTemplate::SideBySide;
+        assert_eq!(side_by_side.max_tracks(), Some(2));
+        assert_eq!(
+            side_by_side.slot(1, 1920, 1080),
+            Some(LayoutSlot { x: 960, y: 0, width: 960, height: 1080 })
+        );
+        assert_eq!(side_by_side.slot(2, 1920, 1080), None);
+
+        // 70% of 1280 = 896, remaining 384
+        let presenter = LayoutTemplate::Presenter;
+        assert_eq!(presenter.slot(0, 1280, 720).unwrap().width, 896);
+        assert_eq!(presenter.slot(1, 1280, 720).unwrap().width, 384);
+
+        // Odd canvas sizes are rounded down to even values
+        let grid = LayoutTemplate::Grid;
+        assert_eq!(
+            grid.slot(3, 1282, 722),
+            Some(LayoutSlot { x: 640, y: 360, width: 640, height: 360 })
+        );
+
+        assert!(!LayoutTemplate::Pip.is_tiled());
+        assert_eq!(LayoutTemplate::Pip.max_tracks(), None);
+    }
+}
@@ -0,0 +1,123 @@
+// Master audio bus processing applied to the final mix
+use serde::{Deserialize, Serialize};
+
+/// Compressor presets for the master bus
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressorPreset {
+    Speech,     // Firm leveling for voice: low threshold, 3:1, fast attack
+    Music,      // Gentle glue for music beds: 2:1, slower attack and release
+}
+
+/// Master bus chain, run on the mixed audio after all tracks are combined
+/// Every stage is optional; the default chain leaves the mix untouched
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MasterBus {
+    #[serde(default)]
+    pub high_pass_hz: Option<f64>,              // Cut rumble below this frequency (e.g. 80)
+    #[serde(default)]
+    pub compressor: Option<CompressorPreset>,
+    #[serde(default)]
+    pub de_esser: bool,                         // Tame sibilance after compression
+    #[serde(default)]
+    pub limiter_ceiling_db: Option<f64>,        // Brickwall ceiling in dBFS (e.g. -1.0)
+}
+
+impl MasterBus {
+    /// Validate stage parameters
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(frequency) = self.high_pass_hz {
+            if !(20.0..=1000.0).contains(&frequency) {
+                return Err(format!("High-pass frequency must be between 20 and 1000 Hz, got {}", frequency));
+            }
+        }
+        if let Some(ceiling) = self.limiter_ceiling_db {
+            // alimiter accepts limits between 0.0625 (-24 dB) and 1.0 (0 dB)
+            if !(-24.0..=0.0).contains(&ceiling) {
+                return Err(format!("Limiter ceiling must be between -24 and 0 dBFS, got {}", ceiling));
+            }
+        }
+        Ok(())
+    }
+
+    /// FFmpeg filter chain for the enabled stages, in order:
+    /// high-pass -> compressor -> de-esser -> limiter
+    /// Returns None when no stage is enabled
+    ///
+    /// Example: "highpass=f=80:poles=2,acompressor=threshold=-20dB:ratio=3:attack=5:release=100:makeup=2,deesser,alimiter=limit=-1.0dB:attack=5:release=50:level=0"
+    pub fn filter_chain(&self) -> Option<String> {
+        let mut filters = Vec::new();
+
+        if let Some(frequency) = self.high_pass_hz {
+            filters.push(format!("highpass=f={}:poles=2", frequency));
+        }
+
+        match self.compressor {
+            Some(CompressorPreset::Speech) => {
+                filters.push("acompressor=threshold=-20dB:ratio=3:attack=5:release=100:makeup=2".to_string());
+            }
+            Some(CompressorPreset::Music) => {
+                filters.push("acompressor=threshold=-16dB:ratio=2:attack=20:release=250:makeup=1.5".to_string());
+            }
+            None => {}
+        }
+
+        if self.de_esser {
+            filters.push("deesser".to_string());
+        }
+
+        if let Some(ceiling) = self.limiter_ceiling_db {
+            // level=0 keeps alimiter from normalizing the output up to the ceiling
+            filters.push(format!("alimiter=limit={:.1}dB:attack=5:release=50:level=0", ceiling));
+        }
+
+        if filters.is_empty() {
+            None
+        } else {
+            Some(filters.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_master_filter_chain() {
+        assert_eq!(MasterBus::default().filter_chain(), None);
+
+        let master = MasterBus {
+            high_pass_hz: Some(80.0),
+            compressor: Some(CompressorPreset::Speech),
+            de_esser: true,
+            limiter_ceiling_db: Some(-1.0),
+        };
+        assert_eq!(
+            master.filter_chain().unwrap(),
+            "highpass=f=80:poles=2,acompressor=threshold=-20dB:ratio=3:attack=5:release=100:makeup=2,deesser,alimiter=limit=-1.0dB:attack=5:release=50:level=0"
+        );
+
+        let limiter_only = MasterBus {
+            limiter_ceiling_db: Some(-0.5),
+            ..Default::default()
+        };
+        assert_eq!(limiter_only.filter_chain().unwrap(), "alimiter=limit=-0.5dB:attack=5:release=50:level=0");
+    }
+
+    #[test]
+    fn test_master_validation() {
+        let mut master = MasterBus {
+            high_pass_hz: Some(10.0),
+            ..Default::default()
+        };
+        assert!(master.validate().is_err());
+
+        master.high_pass_hz = Some(100.0);
+        master.limiter_ceiling_db = Some(1.0);
+        assert!(master.validate().is_err());
+
+        master.limiter_ceiling_db = Some(-1.0);
+        assert!(master.validate().is_ok());
+    }
+}
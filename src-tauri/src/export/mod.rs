@@ -1,8 +1,27 @@
 // Export module for ClipForge
 // Handles timeline export to video files using FFmpeg
 
+pub mod animation;
+pub mod audio;
+pub mod cache;
+pub mod chunks;
 pub mod encoder;
+pub mod frame;
+pub mod graph;
+pub mod layout;
+pub mod master;
 pub mod pipeline;
+pub mod plan;
+pub mod range;
+pub mod sequence;
+pub mod smart_render;
+pub mod stems;
+pub mod streaming;
+pub mod tracks;
+pub mod transitions;
+pub mod workers;
 
-pub use encoder::{ExportSettings, Resolution};
+pub use encoder::{ExportSettings, GapFill, Renderer, Resolution};
+pub use layout::LayoutTemplate;
+pub use master::MasterBus;
 pub use pipeline::ExportPipeline;
@@ -3,8 +3,21 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 
+use crate::ffmpeg::capabilities::Feature;
+use crate::ffmpeg::recorder::CommandRecorder;
+use crate::ffmpeg::workspace::{self, JobWorkspace};
 use crate::ffmpeg::FFmpegWrapper;
-use super::encoder::{ExportSettings, Resolution};
+use super::cache::RenderCache;
+use super::chunks;
+use super::encoder::{ExportSettings, GapFill, Renderer, Resolution};
+use super::graph;
+use super::layout::LayoutTemplate;
+use super::plan::{ExportRoute, RenderPlan};
+use super::range::ExportRange;
+use super::smart_render::{self, SegmentMode, SmartRenderPlan};
+use super::tracks::{self, TrackData};
+use super::transitions::{self, TrackSegment, TransitionOptions};
+use super::workers::WorkerPool;
 
 /// Clip data from timeline (sent from frontend)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,12 +38,72 @@ pub struct ClipData {
     pub fade_in_duration: f64,    // Fade in duration in seconds
     #[serde(default)]
     pub fade_out_duration: f64,   // Fade out duration in seconds
+    // Zoom-and-pan regions (screen recordings)
+    #[serde(default)]
+    pub zoom_regions: Vec<ZoomRegionData>,
+    // Split audio/video edits
+    #[serde(default)]
+    pub audio_lead: f64,          // Audio starts this many seconds before the video (J-cut)
+    #[serde(default)]
+    pub audio_lag: f64,           // Audio continues this many seconds past the video (L-cut)
+    // Unlinked clips use only one of their streams
+    #[serde(default = "default_enabled")]
+    pub video_enabled: bool,      // false = audio-only clip
+    #[serde(default = "default_enabled")]
+    pub audio_enabled: bool,      // false = video-only clip
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 fn default_volume() -> u32 {
     100
 }
 
+/// Two-second test video shared by the unit and export tests
+#[cfg(test)]
+pub const TEST_VIDEO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/test_video_2s.mp4");
+
+#[cfg(test)]
+impl ClipData {
+    /// Clip of the test video on track 0, with no effects
+    pub fn fixture(id: u32, start_time: f64, in_point: f64, out_point: f64) -> Self {
+        ClipData {
+            id,
+            path: TEST_VIDEO.to_string(),
+            in_point,
+            out_point,
+            start_time,
+            track: 0,
+            volume: 100,
+            is_muted: false,
+            fade_in_duration: 0.0,
+            fade_out_duration: 0.0,
+            zoom_regions: vec![],
+            audio_lead: 0.0,
+            audio_lag: 0.0,
+            video_enabled: true,
+            audio_enabled: true,
+        }
+    }
+}
+
+/// Zoom-and-pan region within a clip (sent from frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoomRegionData {
+    pub start_time: f64,        // Start relative to the clip's first frame (seconds)
+    pub duration: f64,          // Duration including ease in/out (seconds)
+    pub x: f64,                 // Target left edge (percentage of frame width)
+    pub y: f64,                 // Target top edge (percentage of frame height)
+    pub width: f64,             // Target width (percentage of frame width)
+    pub height: f64,            // Target height (percentage of frame height)
+    #[serde(default)]
+    pub ease_in: f64,           // Time to punch in from the full frame (seconds)
+    #[serde(default)]
+    pub ease_out: f64,          // Time to return to the full frame (seconds)
+}
+
 /// Text overlay data from timeline (sent from frontend)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextOverlayData {
@@ -46,19 +119,110 @@ pub struct TextOverlayData {
     pub animation: String,      // Animation type (none, fadeIn, fadeOut, slideIn*)
 }
 
+/// How a redaction region hides the content underneath it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    Blur,       // Gaussian-style blur (boxblur)
+    Pixelate,   // Mosaic blocks (pixelize)
+    Box,        // Solid filled rectangle (drawbox)
+}
+
+/// Redaction region from timeline (sent from frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionData {
+    pub id: u32,
+    pub start_time: f64,        // Position on timeline (seconds)
+    pub duration: f64,          // Duration (seconds)
+    pub x: f64,                 // Left edge (percentage of frame width)
+    pub y: f64,                 // Top edge (percentage of frame height)
+    pub width: f64,             // Region width (percentage of frame width)
+    pub height: f64,            // Region height (percentage of frame height)
+    pub mode: RedactionMode,
+    #[serde(default = "default_redaction_strength")]
+    pub strength: u32,          // Blur radius or pixel block size in pixels
+    #[serde(default = "default_redaction_color")]
+    pub color: String,          // Fill color for box mode (#RRGGBB)
+}
+
+fn default_redaction_strength() -> u32 {
+    20
+}
+
+fn default_redaction_color() -> String {
+    "#000000".to_string()
+}
+
 /// Transition data from timeline (sent from frontend)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransitionData {
     pub id: u32,
     pub clip_id_before: u32,   // ID of clip before transition
     pub clip_id_after: u32,    // ID of clip after transition
-    pub transition_type: String, // Type: fade, crossfade, fadeToBlack, wipeLeft, wipeRight, dissolve
+    pub transition_type: String, // Type id from the transition library (see list_transitions)
     pub duration: f64,         // Duration in seconds
+    #[serde(default)]
+    pub options: TransitionOptions, // Direction, softness, color, custom expression
+    #[serde(default)]
+    pub audio_offset: f64,     // Shift the audio cut: negative = audio leads (J-cut), positive = audio lags (L-cut)
+}
+
+/// Gaps shorter than this (seconds) are treated as rounding noise between adjacent clips
+pub(super) const MIN_GAP: f64 = 0.001;
+
+/// Video encoder arguments of trimmed intermediates and gap segments (part of their render cache key)
+const INTERMEDIATE_VIDEO_ENCODING: &[&str] = &["-c:v", "libx264", "-crf", "23"];
+
+/// Audio encoder arguments of trimmed intermediates and gap segments (part of their render cache key)
+const INTERMEDIATE_AUDIO_ENCODING: &[&str] = &["-c:a", "aac", "-b:a", "192k"];
+
+/// -ss and -t values of a trimmed clip
+///
+/// The render cache key is built from the same strings, so clips whose cuts differ by
+/// any amount FFmpeg would see never share a cached segment.
+fn trim_range_args(start_time: f64, end_time: f64) -> (String, String) {
+    (start_time.to_string(), (end_time - start_time).to_string())
+}
+
+/// Which streams a trimmed intermediate file carries
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TrimStreams {
+    /// Sequential track: video (black for audio-only clips) plus audio
+    Sequential,
+    /// Sequential track whose audio is rendered separately: video only (black for audio-only clips)
+    SequentialVideo,
+    /// Overlay track: only the streams the clip has enabled
+    Enabled,
+}
+
+/// One intermediate rendered by trim_clips
+#[derive(Debug, Clone, Copy)]
+enum TrimJob<'a> {
+    Gap(f64),               // Gap segment of this duration
+    Clip(&'a ClipData),     // Trimmed clip
+}
+
+impl TrimJob<'_> {
+    /// Planned length of the segment (seconds)
+    fn duration(&self) -> f64 {
+        match *self {
+            TrimJob::Gap(duration) => duration,
+            TrimJob::Clip(clip) => clip.out_point - clip.in_point,
+        }
+    }
+}
+
+/// Audio range for one clip when audio and video cuts don't line up
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SplitAudioSegment {
+    source_start: f64,      // Start time in source (seconds)
+    duration: f64,          // Length of audio taken from source (seconds)
+    timeline_start: f64,    // Position in the exported audio (seconds)
 }
 
 /// Export pipeline for processing timeline clips into final video
 pub struct ExportPipeline {
-    ffmpeg: Arc<Mutex<FFmpegWrapper>>,
+    pub(super) ffmpeg: Arc<Mutex<FFmpegWrapper>>,
 }
 
 impl ExportPipeline {
@@ -78,10 +242,98 @@ impl ExportPipeline {
         clips: Vec<ClipData>,
         transitions: Vec<TransitionData>,
         text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
         settings: ExportSettings,
     ) -> Result<String, String> {
+        let output_path = settings.output_path.clone();
+        self.render_timeline(clips, transitions, text_overlays, redactions, tracks, settings)?;
+        Ok(output_path)
+    }
+
+    /// Plan an export without running FFmpeg (dry run)
+    ///
+    /// The export runs as usual against a recording FFmpeg wrapper, so the plan lists exactly
+    /// the commands a real export would run (FFprobe still probes the sources). With
+    /// settings.script_path the plan is also written there as a shell script.
+    pub fn plan_timeline(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: ExportSettings,
+    ) -> Result<RenderPlan, String> {
+        let recorder = CommandRecorder::default();
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .recording(recorder.clone());
+        let dry_run = ExportPipeline::new(Arc::new(Mutex::new(ffmpeg)));
+
+        let output_path = settings.output_path.clone();
+        let script_path = settings.script_path.clone();
+        let (route, expected_duration) = dry_run.render_timeline(clips, transitions, text_overlays, redactions, tracks, settings)?;
+        let mut plan = RenderPlan::new(route, recorder.commands(), output_path, expected_duration);
+        eprintln!("📝 Dry run planned {} FFmpeg commands ({:?}, {:.3}s)", plan.commands.len(), route, expected_duration);
+
+        if let Some(path) = script_path {
+            let script = plan.shell_script(&workspace::workspaces_root().display().to_string());
+            std::fs::write(&path, script)
+                .map_err(|e| format!("Failed to write render script: {}", e))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755));
+            }
+            plan.script_path = Some(path);
+        }
+
+        Ok(plan)
+    }
+
+    /// Validate the timeline and render it on the path that suits it (see export_timeline)
+    /// Returns the path taken and the expected output duration
+    pub(super) fn render_timeline(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: ExportSettings,
+    ) -> Result<(ExportRoute, f64), String> {
+        let outputs = [
+            settings.animation.is_some(),
+            settings.audio_only.is_some(),
+            settings.image_sequence.is_some(),
+            settings.streaming.is_some(),
+        ];
+        if outputs.iter().filter(|&&set| set).count() > 1 {
+            return Err("An export can be only one of an animated image, audio only, an image sequence or a streaming ladder".to_string());
+        }
+
+        if (settings.animation.is_some() || settings.streaming.is_some()) && settings.stems.is_some() {
+            return Err("Stems can't be exported with animated images or streaming ladders".to_string());
+        }
+
+        // Stems: the main render, then one mix per stem
+        if let Some(stems) = settings.stems.clone() {
+            return self.render_with_stems(clips, transitions, text_overlays, redactions, tracks, settings, &stems);
+        }
+
+        // Animated images: render to an intermediate video, then convert it
+        if let Some(animation) = settings.animation.clone() {
+            return self.render_animation(clips, transitions, text_overlays, redactions, tracks, settings, &animation);
+        }
+
+        // Streaming ladders: render to an intermediate video, then encode and package the renditions
+        if let Some(streaming) = settings.streaming.clone() {
+            return self.render_streaming(clips, transitions, text_overlays, redactions, tracks, settings, &streaming);
+        }
+
         // Debug: Log incoming data
-        eprintln!("🎬 Export started with {} clips, {} transitions, {} text overlays", clips.len(), transitions.len(), text_overlays.len());
+        eprintln!("🎬 Export started with {} clips, {} transitions, {} text overlays, {} redactions", clips.len(), transitions.len(), text_overlays.len(), redactions.len());
         for (i, overlay) in text_overlays.iter().enumerate() {
             eprintln!("   Overlay {}: '{}' at ({}, {}) from {:.2}s for {:.2}s", i, overlay.text, overlay.x, overlay.y, overlay.start_time, overlay.duration);
         }
@@ -92,38 +344,562 @@ impl ExportPipeline {
             ffmpeg.set_progress(0.0, "Starting export...".to_string(), None);
         }
 
+        let (clips, transitions, text_overlays, redactions) = self.prepare_timeline(
+            clips, transitions, text_overlays, redactions, &tracks, settings.range
+        )?;
+
         // Validate input
         if clips.is_empty() {
             return Err("No clips to export".to_string());
         }
 
-        // Validate all source files exist
-        for clip in &clips {
-            if !Path::new(&clip.path).exists() {
-                return Err(format!("Source file not found: {}", clip.path));
+        self.validate_timeline(&clips, &transitions, &text_overlays, &redactions)?;
+
+        settings.master.validate()?;
+
+        if let Some(seconds) = settings.chunk_duration {
+            if !(seconds.is_finite() && seconds > 0.0) {
+                return Err(format!("Chunk duration must be a positive number of seconds, got {}", seconds));
             }
         }
 
+        if let Some(slate) = &settings.gap_fill.slate_path {
+            if !Path::new(slate).exists() {
+                return Err(format!("Gap slate image not found: {}", slate));
+            }
+        }
+        ffmpeg_color(&settings.gap_fill.color).map_err(|e| format!("Gap fill: {}", e))?;
+
         // Check if clips actually overlap in time (not just on different tracks)
         // Overlapping = same time range, different tracks (Picture-in-Picture)
         // Non-overlapping = sequential clips, even if on different tracks
-        let has_temporal_overlap = clips.iter().any(|clip1| {
-            clips.iter().any(|clip2| {
-                clip1.track != clip2.track && // Different tracks
-                !(clip1.start_time + (clip1.out_point - clip1.in_point) <= clip2.start_time || // clip1 ends before clip2 starts
-                  clip2.start_time + (clip2.out_point - clip2.in_point) <= clip1.start_time)   // clip2 ends before clip1 starts
-            })
-        });
+        let has_temporal_overlap = self.has_temporal_overlap(&clips);
+        let expected_duration = self.expected_duration(&clips, &transitions, has_temporal_overlap);
+
+        // Only the multi-pass single-track encode is split into chunks; say so instead of ignoring it
+        if settings.chunk_duration.is_some() {
+            let unsupported = if settings.audio_only.is_some() || settings.image_sequence.is_some() {
+                Some("audio-only exports or image sequences")
+            } else if settings.renderer == Renderer::SinglePass {
+                Some("the single-pass renderer")
+            } else if has_temporal_overlap {
+                Some("overlapping tracks")
+            } else if !transitions.is_empty() {
+                Some("transitions")
+            } else {
+                None
+            };
+            if let Some(route) = unsupported {
+                return Err(format!("Chunked encoding can't be used with {}; remove chunk_duration", route));
+            }
+        }
+
+        // Audio-only exports and image sequences skip the video routes (and their requirements)
+        if let Some(audio) = &settings.audio_only {
+            eprintln!("📹 Taking AUDIO-ONLY path");
+            self.export_audio_only(&clips, &transitions, &tracks, &settings, audio)?;
+            return Ok((ExportRoute::AudioOnly, expected_duration));
+        }
+
+        if let Some(sequence) = &settings.image_sequence {
+            eprintln!("📹 Taking IMAGE SEQUENCE path");
+            self.export_image_sequence(&clips, &transitions, &text_overlays, &redactions, &tracks, &settings, sequence)?;
+            return Ok((ExportRoute::ImageSequence, expected_duration));
+        }
+
+        // Reject exports the FFmpeg build can't do before any work starts
+        let required = self.required_features(&clips, &transitions, &text_overlays, &redactions, has_temporal_overlap, &settings);
+        self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .require(&required)?;
+
+        // Untouched single-track timelines can be stream-copied instead of re-encoded
+        if !has_temporal_overlap {
+            match self.plan_smart_render(&clips, &transitions, &text_overlays, &redactions, &tracks, &settings) {
+                Ok(plan) => {
+                    eprintln!("📹 Taking SMART RENDER path ({} clips)", plan.clips.len());
+                    match self.export_smart_render(&plan, &settings) {
+                        Ok(_) => return Ok((ExportRoute::SmartRender, expected_duration)),
+                        Err(e) => {
+                            eprintln!("   ⚠️  Smart render failed, re-encoding instead: {}", e);
+                            // Don't leave a half-joined file behind if the re-encode fails too
+                            let _ = std::fs::remove_file(&settings.output_path);
+                        }
+                    }
+                }
+                Err(reason) => eprintln!("   Smart render not used: {}", reason),
+            }
+        }
+
+        if settings.renderer == Renderer::SinglePass {
+            eprintln!("📹 Taking SINGLE-PASS path");
+            self.export_single_pass(&clips, &transitions, &text_overlays, &redactions, &tracks, &settings)?;
+            return Ok((ExportRoute::SinglePass, expected_duration));
+        }
 
         if has_temporal_overlap {
             // Multi-track export with overlays (Picture-in-Picture)
             eprintln!("📹 Taking MULTITRACK path (temporal overlap detected)");
-            self.export_multitrack(clips, transitions, text_overlays, settings)
+            self.export_multitrack(clips, transitions, text_overlays, redactions, &tracks, settings)?;
+            Ok((ExportRoute::Multitrack, expected_duration))
         } else {
             // Single-track export - concatenate all clips sequentially with transitions
             eprintln!("📹 Taking SINGLETRACK path");
-            self.export_singletrack(clips, transitions, text_overlays, settings)
+            self.export_singletrack(clips, transitions, text_overlays, redactions, &tracks, settings)?;
+            Ok((ExportRoute::Singletrack, expected_duration))
+        }
+    }
+
+    /// Cut the timeline to the export range (if any) and apply the track settings
+    #[allow(clippy::type_complexity)]
+    pub(super) fn prepare_timeline(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: &[TrackData],
+        range: Option<ExportRange>,
+    ) -> Result<(Vec<ClipData>, Vec<TransitionData>, Vec<TextOverlayData>, Vec<RedactionData>), String> {
+        // Export range: cut everything to the window, re-based so the window starts at 0
+        let (clips, transitions, text_overlays, redactions) = match range {
+            Some(range) => {
+                range.validate()?;
+                let clips = range.clips(&clips);
+                if clips.is_empty() {
+                    return Err(format!("No clips between {:.3}s and {:.3}s", range.start, range.end));
+                }
+                eprintln!("   Export range {:.3}s-{:.3}s: {} clips", range.start, range.end, clips.len());
+                let transitions = range.transitions(&transitions, &clips);
+                (clips, transitions, range.text_overlays(&text_overlays), range.redactions(&redactions))
+            }
+            None => (clips, transitions, text_overlays, redactions),
+        };
+
+        // Track settings: hidden tracks drop their video, muted (or non-soloed) tracks their audio
+        tracks::validate(tracks)?;
+        let clips = self.apply_track_settings(clips, tracks);
+        Ok((clips, transitions, text_overlays, redactions))
+    }
+
+    /// Length of the output: the end of the base sequence (shortened by its transitions),
+    /// or of the last overlay when clips overlap in time (picture-in-picture)
+    fn expected_duration(&self, clips: &[ClipData], transitions: &[TransitionData], has_temporal_overlap: bool) -> f64 {
+        let mut sequence: Vec<ClipData> = clips.iter()
+            .filter(|c| !has_temporal_overlap || c.track == 0)
+            .cloned()
+            .collect();
+        sequence.sort_by(|a, b| {
+            a.start_time.partial_cmp(&b.start_time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let sequence_end = match (self.output_positions(&sequence, transitions).last(), sequence.last()) {
+            (Some(position), Some(clip)) => position + (clip.out_point - clip.in_point),
+            _ => 0.0,
+        };
+        clips.iter()
+            .filter(|c| has_temporal_overlap && c.track > 0)
+            .map(|c| c.start_time + (c.out_point - c.in_point))
+            .fold(sequence_end, f64::max)
+    }
+
+    /// Fold track visibility and audibility into each clip's video_enabled / audio_enabled,
+    /// then drop clips with both streams switched off (they contribute nothing)
+    pub(super) fn apply_track_settings(&self, clips: Vec<ClipData>, tracks: &[TrackData]) -> Vec<ClipData> {
+        clips.into_iter()
+            .map(|mut c| {
+                c.video_enabled = c.video_enabled && tracks::is_visible(tracks, c.track);
+                c.audio_enabled = c.audio_enabled && tracks::is_audible(tracks, c.track);
+                c
+            })
+            .filter(|c| {
+                if !c.video_enabled && !c.audio_enabled {
+                    eprintln!("   Skipping clip {} (video and audio disabled)", c.id);
+                }
+                c.video_enabled || c.audio_enabled
+            })
+            .collect()
+    }
+
+    /// Check a prepared timeline before anything renders: source files exist, and zoom
+    /// regions, transitions, redactions and text overlays are valid
+    pub(super) fn validate_timeline(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+    ) -> Result<(), String> {
+        for clip in clips {
+            if !Path::new(&clip.path).exists() {
+                return Err(format!("Source file not found: {}", clip.path));
+            }
+            self.validate_zoom_regions(clip)?;
+        }
+
+        self.validate_transitions(clips, transitions)?;
+
+        // Redaction regions up front so a bad region doesn't fail mid-encode
+        for redaction in redactions {
+            self.validate_redaction(redaction)?;
+        }
+
+        // Text overlays too, so every renderer exports the same captions
+        for overlay in text_overlays {
+            self.validate_text_overlay(overlay)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that each transition is in the transition library and joins a clip to the
+    /// clip right after it, with both long enough to cover the transition
+    ///
+    /// Transitions are only rendered on sequential timelines, so overlapping tracks
+    /// (picture-in-picture) can't have them.
+    fn validate_transitions(&self, clips: &[ClipData], transitions: &[TransitionData]) -> Result<(), String> {
+        if transitions.is_empty() {
+            return Ok(());
+        }
+        if self.has_temporal_overlap(clips) {
+            return Err("Transitions can't be rendered when clips on different tracks overlap".to_string());
+        }
+
+        let mut sorted_clips = clips.to_vec();
+        sorted_clips.sort_by(|a, b| {
+            a.start_time.partial_cmp(&b.start_time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let positions = self.timeline_positions(&sorted_clips);
+
+        // Time each clip spends in transitions (it may have one on both sides)
+        let mut overlapped = vec![0.0; sorted_clips.len()];
+        for transition in transitions {
+            transitions::xfade_options(transition)?;
+
+            let index = sorted_clips.iter()
+                .position(|c| c.id == transition.clip_id_before)
+                .filter(|&index| sorted_clips.get(index + 1).is_some_and(|next| next.id == transition.clip_id_after))
+                .ok_or_else(|| format!(
+                    "Transition {} must join clip {} to the clip right after it",
+                    transition.id, transition.clip_id_before
+                ))?;
+            let before = &sorted_clips[index];
+            if positions[index + 1] - (positions[index] + (before.out_point - before.in_point)) > MIN_GAP {
+                return Err(format!("Transition {} can't cross the gap after clip {}", transition.id, before.id));
+            }
+            overlapped[index] += transition.duration;
+            overlapped[index + 1] += transition.duration;
+        }
+
+        for (clip, overlapped) in sorted_clips.iter().zip(overlapped) {
+            if overlapped > clip.out_point - clip.in_point {
+                return Err(format!("Clip {} is shorter than its transitions", clip.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Filters the timeline's effects and compositing use, whatever the output
+    pub(super) fn timeline_features(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        has_temporal_overlap: bool,
+    ) -> Vec<(Feature, &'static str)> {
+        let mut required = Vec::new();
+        if !text_overlays.is_empty() {
+            required.push((Feature::Filter("drawtext"), "text overlays"));
+        }
+        if !transitions.is_empty() {
+            required.push((Feature::Filter("xfade"), "transitions"));
+            required.push((Feature::Filter("acrossfade"), "transitions"));
+        }
+        for redaction in redactions {
+            required.push(match redaction.mode {
+                RedactionMode::Blur => (Feature::Filter("boxblur"), "blur redactions"),
+                RedactionMode::Pixelate => (Feature::Filter("pixelize"), "pixelate redactions"),
+                RedactionMode::Box => (Feature::Filter("drawbox"), "box redactions"),
+            });
+        }
+        if clips.iter().any(|c| !c.zoom_regions.is_empty()) {
+            required.push((Feature::Filter("zoompan"), "zoom regions"));
+        }
+        if clips.iter().any(|c| c.fade_in_duration > 0.0 || c.fade_out_duration > 0.0) {
+            required.push((Feature::Filter("afade"), "audio fades"));
+        }
+        if has_temporal_overlap {
+            required.push((Feature::Filter("overlay"), "picture-in-picture"));
+        }
+        required
+    }
+
+    /// Encoders, filters and muxers an export uses, each with what needs it
+    fn required_features(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        has_temporal_overlap: bool,
+        settings: &ExportSettings,
+    ) -> Vec<(Feature, &'static str)> {
+        let mut required = vec![
+            (Feature::Encoder("libx264"), "video encoding"),
+            (Feature::Encoder("aac"), "audio encoding"),
+        ];
+
+        let extension = Path::new(&settings.output_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        match extension.as_deref() {
+            Some("mp4") => required.push((Feature::Muxer("mp4"), "MP4 output")),
+            Some("mov") => required.push((Feature::Muxer("mov"), "MOV output")),
+            Some("mkv") => required.push((Feature::Muxer("matroska"), "MKV output")),
+            _ => {}
+        }
+
+        if settings.resolution.scale_filter().is_some() {
+            required.push((Feature::Filter("scale"), "the export resolution"));
+        }
+        required.extend(self.timeline_features(clips, transitions, text_overlays, redactions, has_temporal_overlap));
+
+        let master = &settings.master;
+        if master.high_pass_hz.is_some() {
+            required.push((Feature::Filter("highpass"), "the master bus high-pass"));
+        }
+        if master.compressor.is_some() {
+            required.push((Feature::Filter("acompressor"), "the master bus compressor"));
+        }
+        if master.de_esser {
+            required.push((Feature::Filter("deesser"), "the master bus de-esser"));
+        }
+        if master.limiter_ceiling_db.is_some() {
+            required.push((Feature::Filter("alimiter"), "the master bus limiter"));
+        }
+
+        required
+    }
+
+    /// Whether clips on different tracks play at the same time (picture-in-picture)
+    pub(super) fn has_temporal_overlap(&self, clips: &[ClipData]) -> bool {
+        clips.iter().any(|clip1| {
+            clips.iter().any(|clip2| {
+                clip1.track != clip2.track && // Different tracks
+                !(clip1.start_time + (clip1.out_point - clip1.in_point) <= clip2.start_time || // clip1 ends before clip2 starts
+                  clip2.start_time + (clip2.out_point - clip2.in_point) <= clip1.start_time)   // clip2 ends before clip1 starts
+            })
+        })
+    }
+
+    /// Plan a stream-copy render of a single-track timeline
+    ///
+    /// Returns Err with the reason when the export asks for the single-pass renderer or
+    /// anything in it needs the normal render path: overlays, redactions, transitions,
+    /// scaling, master bus, gaps, per-clip effects, chunked encoding, or sources whose codec
+    /// parameters can't be copied and concatenated together.
+    fn plan_smart_render(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+    ) -> Result<SmartRenderPlan, String> {
+        if !settings.smart_render {
+            return Err("disabled in export settings".to_string());
+        }
+        if settings.renderer == Renderer::SinglePass {
+            return Err("single-pass renderer requested".to_string());
+        }
+        if settings.chunk_duration.is_some() {
+            return Err("chunked encoding requested".to_string());
+        }
+        if !text_overlays.is_empty() || !redactions.is_empty() {
+            return Err("text overlays or redactions need re-encoding".to_string());
+        }
+        if !transitions.is_empty() {
+            return Err("transitions need re-encoding".to_string());
+        }
+        if settings.master.filter_chain().is_some() {
+            return Err("master bus processing needs re-encoding".to_string());
+        }
+        if !smart_render::container_supported(&settings.output_path) {
+            return Err("output container doesn't support stream copy".to_string());
+        }
+        if self.has_split_edits(clips, transitions) {
+            return Err("J/L cuts need re-encoding".to_string());
+        }
+        for clip in clips {
+            if let Some(reason) = smart_render::clip_effects(clip, tracks) {
+                return Err(reason);
+            }
+        }
+
+        let mut sorted_clips = clips.to_vec();
+        sorted_clips.sort_by(|a, b| {
+            a.start_time.partial_cmp(&b.start_time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        // Clips must butt up against each other from the start of the timeline
+        let positions = self.timeline_positions(&sorted_clips);
+        let mut expected_start = 0.0;
+        for (clip, position) in sorted_clips.iter().zip(&positions) {
+            if (clip.start_time - expected_start).abs() > MIN_GAP || (position - clip.start_time).abs() > MIN_GAP {
+                return Err("timeline has gaps or overlaps".to_string());
+            }
+            expected_start = position + (clip.out_point - clip.in_point);
+        }
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
+
+        let source = ffmpeg.probe(&sorted_clips[0].path)?;
+        let encode_args = smart_render::head_encoder_args(&source)?;
+        if let Some((width, height)) = settings.resolution.dimensions() {
+            if (width, height) != (source.width, source.height) {
+                return Err("output is scaled".to_string());
+            }
+        }
+
+        let mut segments = Vec::new();
+        for (index, clip) in sorted_clips.iter().enumerate() {
+            let metadata = ffmpeg.probe(&clip.path)?;
+            smart_render::streams_compatible(&source, &metadata)
+                .map_err(|e| format!("clip {}: {}", clip.id, e))?;
+
+            let keyframes = ffmpeg.probe_keyframes(&clip.path, clip.in_point, clip.out_point)?;
+            segments.extend(smart_render::plan_clip(index, clip.in_point, clip.out_point, &keyframes));
+        }
+
+        Ok(SmartRenderPlan {
+            clips: sorted_clips,
+            segments,
+            source,
+            encode_args,
+        })
+    }
+
+    /// Execute a smart render plan
+    ///
+    /// Each segment is written as Annex B MPEG-TS (copied through a bitstream filter, or
+    /// re-encoded with the plan's source-matched encoder arguments), then all segments are
+    /// joined with the concat demuxer using stream copy.
+    fn export_smart_render(&self, plan: &SmartRenderPlan, settings: &ExportSettings) -> Result<String, String> {
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
+
+        let workspace = JobWorkspace::create("smart_render")?;
+
+        let mut segment_files: Vec<PathBuf> = Vec::new();
+        let total_segments = plan.segments.len();
+        for (index, segment) in plan.segments.iter().enumerate() {
+            let clip = &plan.clips[segment.clip_index];
+            let mode_label = if segment.mode == SegmentMode::Copy { "Copying" } else { "Re-encoding" };
+            ffmpeg.set_progress(
+                (index as f64 / total_segments as f64) * 90.0,
+                format!("{} segment {} of {}...", mode_label, index + 1, total_segments),
+                None
+            );
+
+            let segment_path = workspace.file(&format!("smart_{}.ts", index));
+            let mut args: Vec<String> = vec![
+                "-ss".to_string(),
+                format!("{:.6}", segment.start),
+                "-i".to_string(),
+                clip.path.clone(),
+                "-t".to_string(),
+                format!("{:.6}", segment.end - segment.start),
+                "-map".to_string(),
+                "0:v:0".to_string(),
+                "-map".to_string(),
+                "0:a:0".to_string(),
+            ];
+            match segment.mode {
+                SegmentMode::Copy => {
+                    args.extend(["-c", "copy", "-bsf:v", plan.annexb_filter()].iter().map(|s| s.to_string()));
+                }
+                SegmentMode::Encode => args.extend(plan.encode_args.iter().cloned()),
+            }
+            args.extend(["-avoid_negative_ts", "make_zero", "-f", "mpegts", "-y"].iter().map(|s| s.to_string()));
+            args.push(segment_path.display().to_string());
+
+            eprintln!("⏩ Smart render segment {} ({:?} {:.3}-{:.3}s): {:?}", index, segment.mode, segment.start, segment.end, args);
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+            segment_files.push(segment_path);
         }
+
+        // Join segments without re-encoding
+        ffmpeg.set_progress(90.0, "Joining segments...".to_string(), None);
+        let concat_list = segment_files
+            .iter()
+            .map(|p| format!("file '{}'", p.display().to_string().replace("'", "'\\''")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let concat_file_path = workspace.file("smart_concat_list.txt");
+        std::fs::write(&concat_file_path, concat_list)
+            .map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+        let concat_list_str = concat_file_path.display().to_string();
+        let args = vec![
+            "-f", "concat",
+            "-safe", "0",
+            "-i", &concat_list_str,
+            "-map", "0:v",
+            "-map", "0:a",
+            "-c", "copy",
+            "-bsf:a", "aac_adtstoasc",  // ADTS (MPEG-TS) to MP4-style AAC
+            "-movflags", "+faststart",
+            "-y",
+            &settings.output_path,
+        ];
+        ffmpeg.execute_command(&args)?;
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(settings.output_path.clone())
+    }
+
+    /// Render the whole timeline with one FFmpeg invocation (see build_single_pass_graph)
+    /// No intermediate files are written; sources are decoded once and encoded once
+    fn export_single_pass(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+    ) -> Result<String, String> {
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
+
+        ffmpeg.set_progress(5.0, "Probing sources...".to_string(), None);
+        let sources = graph::probe_sources(&ffmpeg, clips)?;
+
+        let graph = self.build_single_pass_graph(clips, transitions, text_overlays, redactions, tracks, settings, &sources)?;
+        eprintln!("📊 SINGLE-PASS: {} inputs, filter graph: {}", graph.inputs.len(), graph.filter_complex);
+
+        let mut args = graph.args();
+        args.extend(["-c:v", "libx264", "-crf", "23", "-c:a", "aac", "-b:a", "192k", "-t"].iter().map(|s| s.to_string()));
+        args.push(format!("{:.3}", graph.duration));
+        args.push("-y".to_string());
+        args.push(settings.output_path.clone());
+
+        eprintln!("📊 SINGLE-PASS: Final FFmpeg arguments (count={}): {:?}", args.len(), args);
+
+        ffmpeg.set_progress(10.0, "Encoding video...".to_string(), None);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(settings.output_path.clone())
     }
 
     /// Export single track (track 0 only) with transitions and text overlays
@@ -132,6 +908,8 @@ impl ExportPipeline {
         clips: Vec<ClipData>,
         transitions: Vec<TransitionData>,
         text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: &[TrackData],
         settings: ExportSettings,
     ) -> Result<String, String> {
         eprintln!("🎬 export_singletrack: {} text overlays to apply", text_overlays.len());
@@ -143,87 +921,389 @@ impl ExportPipeline {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        // Create temp directory for intermediate files
-        let temp_dir = std::env::temp_dir().join("clipforge_export");
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+        // Intermediate files live in this export's own workspace (removed when it is dropped)
+        let workspace = JobWorkspace::create("export")?;
+        let temp_dir = workspace.path();
+
+        // J/L cuts and video-only clips: build the audio separately so its cuts can differ
+        // from the video cuts, and video-only clips simply have no audio branch
+        let has_split_audio = self.needs_separate_audio(&sorted_clips, &transitions);
+        let streams = if has_split_audio { TrimStreams::SequentialVideo } else { TrimStreams::Sequential };
 
         // Phase 1: Trim clips to intermediate files
-        let intermediate_files = self.trim_clips(&sorted_clips, &temp_dir)?;
+        let intermediate_files = self.trim_clips(&sorted_clips, temp_dir, streams, tracks, &settings, None)?;
+        let trim_jobs = self.plan_trim_jobs(&sorted_clips, temp_dir, true, None)?;
+        let planned_durations: Vec<f64> = trim_jobs.iter()
+            .map(|(_, job)| job.duration())
+            .collect();
+
+        let split_audio_path = temp_dir.join("split_audio.wav");
+        if has_split_audio {
+            if let Err(e) = self.render_split_audio(&sorted_clips, &transitions, tracks, None, &split_audio_path) {
+                for file in &intermediate_files {
+                    let _ = std::fs::remove_file(file);
+                }
+                return Err(e);
+            }
+        }
 
-        // Phase 2: Concatenate clips with jump cuts (transitions disabled)
-        // Transitions are a stretch goal and have been disabled
-        // Always use fast concat demuxer for jump cuts
-        eprintln!("🎬 Using concatenate_and_encode (jump cuts only - transitions disabled)");
-        let result = self.concatenate_and_encode(&intermediate_files, &text_overlays, &settings);
+        // Phase 2: Join the clips - the concat demuxer for jump cuts, a filter graph for transitions
+        let audio_track = if has_split_audio { Some(split_audio_path.as_path()) } else { None };
+        let result = if !transitions.is_empty() {
+            let segments: Vec<TrackSegment> = trim_jobs.iter()
+                .enumerate()
+                .map(|(index, (_, job))| TrackSegment {
+                    video: format!("[{}:v]", index),
+                    audio: if has_split_audio { None } else { Some(format!("[{}:a]", index)) },
+                    duration: job.duration(),
+                    clip_id: match job {
+                        TrimJob::Clip(clip) => Some(clip.id),
+                        TrimJob::Gap(_) => None,
+                    },
+                })
+                .collect();
+            self.concatenate_with_transitions(&intermediate_files, &segments, audio_track, &transitions, &text_overlays, &redactions, &settings)
+        } else {
+            match settings.chunk_duration {
+                Some(chunk_duration) => self.encode_chunked(
+                    &intermediate_files, &planned_durations, audio_track, &text_overlays, &redactions, &settings, chunk_duration
+                ),
+                None => self.concatenate_and_encode(&intermediate_files, audio_track, &text_overlays, &redactions, &settings),
+            }
+        };
 
         // Clean up temp files
         for file in &intermediate_files {
             let _ = std::fs::remove_file(file);
         }
-        let _ = std::fs::remove_dir(&temp_dir);
+        let _ = std::fs::remove_file(&split_audio_path);
 
         result
     }
 
     /// Trim each clip to create intermediate files
+    /// streams decides which streams each intermediate carries (see TrimStreams)
+    ///
+    /// On sequential tracks, gaps before a clip's start_time become gap segments
+    /// (gap_fill color or slate, plus silence) so the concatenated output keeps timeline timing.
+    /// If sequence_end is past the last clip, a trailing gap segment extends the track to it.
+    ///
+    /// Clips and gaps are independent renders, so they run on a worker pool sized by
+    /// settings.parallelism; the FFmpeg lock is only held to copy the wrapper.
+    /// With settings.render_cache, segments rendered by an earlier export with the same
+    /// inputs are copied from the render cache instead of re-encoded.
     fn trim_clips(
         &self,
         clips: &[ClipData],
         temp_dir: &Path,
+        streams: TrimStreams,
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+        sequence_end: Option<f64>,
     ) -> Result<Vec<PathBuf>, String> {
         let ffmpeg = self.ffmpeg.lock()
-            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
-
-        let mut intermediate_files = Vec::new();
-        let total_clips = clips.len();
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        let sequential = streams != TrimStreams::Enabled;
+        let jobs = self.plan_trim_jobs(clips, temp_dir, sequential, sequence_end)?;
+
+        // Audio-only clips and gaps on a sequential track are filled with frames matching the
+        // first clip that has video, so the concat demuxer sees one consistent format
+        let has_gaps = jobs.iter().any(|(_, job)| matches!(job, TrimJob::Gap(_)));
+        let mut black_frame = (1280, 720, 30.0);
+        if sequential && (has_gaps || clips.iter().any(|c| !c.video_enabled)) {
+            if let Some(video_clip) = clips.iter().find(|c| c.video_enabled) {
+                let metadata = ffmpeg.probe(&video_clip.path)?;
+                black_frame = (metadata.width, metadata.height, metadata.fps);
+            }
+        }
 
-        for (index, clip) in clips.iter().enumerate() {
-            // Update progress: trimming phase is 0-40% of total export
-            let trim_progress = (index as f64 / total_clips as f64) * 40.0;
-            ffmpeg.set_progress(
-                trim_progress,
-                format!("Trimming clip {} of {}...", index + 1, total_clips),
+        let pool = WorkerPool::new(settings.parallelism);
+        // Dry runs render nothing to cache, and list every segment as rendered
+        let cache = if settings.render_cache && !ffmpeg.is_recording() { Some(RenderCache::open_default()) } else { None };
+        let total = jobs.len();
+        eprintln!(
+            "✂️  Trimming {} segments on {} workers ({} threads each)",
+            total, pool.workers, pool.threads_per_job
+        );
+        ffmpeg.set_progress(0.0, format!("Trimming {} clips...", clips.len()), None);
+
+        let result = pool.run(
+            total,
+            |index| {
+                let (path, job) = &jobs[index];
+
+                let key = cache.as_ref().and_then(|_| {
+                    self.trim_cache_key(job, streams, black_frame, tracks, &settings.gap_fill)
+                        .map_err(|e| eprintln!("   ⚠️  Render cache skipped for {}: {}", path.display(), e))
+                        .ok()
+                });
+                if let (Some(cache), Some(key)) = (&cache, &key) {
+                    if cache.fetch(key, path) {
+                        eprintln!("♻️  Reusing cached render for {}", path.display());
+                        return Ok(());
+                    }
+                }
+
+                // A leftover file could be a link to a cache entry; never render into it
+                let _ = std::fs::remove_file(path);
+                match *job {
+                    TrimJob::Gap(duration) => {
+                        let with_audio = streams == TrimStreams::Sequential;
+                        self.render_gap(&ffmpeg, path, duration, black_frame, with_audio, &settings.gap_fill, pool.threads_per_job)
+                    }
+                    TrimJob::Clip(clip) => {
+                        let path_str = path.to_str()
+                            .ok_or_else(|| "Failed to convert path to string (invalid UTF-8)".to_string())?;
+
+                        let (with_video, with_audio) = match streams {
+                            TrimStreams::Sequential => (true, true),
+                            TrimStreams::SequentialVideo => (true, false),
+                            TrimStreams::Enabled => (clip.video_enabled, clip.audio_enabled),
+                        };
+
+                        // Always use audio filtering to ensure output has audio
+                        // This handles clips that may not have audio by generating silence
+                        self.trim_with_audio_filters(
+                            &ffmpeg,
+                            &clip.path,
+                            path_str,
+                            clip.in_point,
+                            clip.out_point,
+                            clip,
+                            with_video,
+                            with_audio,
+                            black_frame,
+                            tracks,
+                            pool.threads_per_job,
+                        )
+                    }
+                }?;
+
+                if let (Some(cache), Some(key)) = (&cache, &key) {
+                    if let Err(e) = cache.store(key, path) {
+                        eprintln!("   ⚠️  Failed to cache {}: {}", path.display(), e);
+                    }
+                }
+                Ok(())
+            },
+            // Trimming phase is 0-40% of total export, whichever worker finishes
+            |done| ffmpeg.set_progress(
+                (done as f64 / total as f64) * 40.0,
+                format!("Trimmed {} of {} segments...", done, total),
                 None
-            );
+            ),
+        );
 
-            let duration = clip.out_point - clip.in_point;
+        let intermediate_files: Vec<PathBuf> = jobs.into_iter().map(|(path, _)| path).collect();
+        if let Err(e) = result {
+            for file in &intermediate_files {
+                let _ = std::fs::remove_file(file);
+            }
+            return Err(e);
+        }
+
+        Ok(intermediate_files)
+    }
 
-            if duration <= 0.0 {
+    /// Every intermediate trim_clips renders, in output order, with its file name
+    /// Gap segments are only planned on sequential tracks (see trim_clips)
+    fn plan_trim_jobs<'a>(
+        &self,
+        clips: &'a [ClipData],
+        temp_dir: &Path,
+        sequential: bool,
+        sequence_end: Option<f64>,
+    ) -> Result<Vec<(PathBuf, TrimJob<'a>)>, String> {
+        let positions = self.timeline_positions(clips);
+        let mut jobs: Vec<(PathBuf, TrimJob)> = Vec::new();
+        for (index, clip) in clips.iter().enumerate() {
+            if clip.out_point - clip.in_point <= 0.0 {
                 return Err(format!("Clip {} has invalid duration", clip.id));
             }
 
-            // Create intermediate file path
-            let intermediate_path = temp_dir.join(format!("clip_{}_trimmed.mp4", index));
-            let path_str = intermediate_path.to_str()
-                .ok_or_else(|| "Failed to convert path to string (invalid UTF-8)".to_string())?;
+            // Fill the gap between the previous clip (or timeline start) and this clip
+            if sequential {
+                let previous_end = if index == 0 {
+                    0.0
+                } else {
+                    positions[index - 1] + (clips[index - 1].out_point - clips[index - 1].in_point)
+                };
+                let gap = positions[index] - previous_end;
+                if gap > MIN_GAP {
+                    jobs.push((temp_dir.join(format!("gap_{}.mp4", index)), TrimJob::Gap(gap)));
+                }
+            }
 
-            // Check if audio filtering is needed
-            // Always re-encode if we need to handle missing audio (generate silence)
-            let needs_audio_filter = clip.is_muted ||
-                                      clip.volume != 100 ||
-                                      clip.fade_in_duration > 0.0 ||
-                                      clip.fade_out_duration > 0.0;
+            jobs.push((temp_dir.join(format!("clip_{}_trimmed.mp4", index)), TrimJob::Clip(clip)));
+        }
 
-            // Always use audio filtering to ensure output has audio
-            // This handles clips that may not have audio by generating silence
-            self.trim_with_audio_filters(
-                &ffmpeg,
-                &clip.path,
-                path_str,
-                clip.in_point,
-                clip.out_point,
-                clip,
-            )?;
+        // Extend the track to the end of the timeline
+        let last_end = match (positions.last(), clips.last()) {
+            (Some(position), Some(clip)) => position + (clip.out_point - clip.in_point),
+            _ => 0.0,
+        };
+        let trailing_gap = sequence_end.map_or(0.0, |end| end - last_end);
+        if sequential && trailing_gap > MIN_GAP {
+            jobs.push((temp_dir.join("gap_end.mp4"), TrimJob::Gap(trailing_gap)));
+        }
+
+        Ok(jobs)
+    }
 
-            intermediate_files.push(intermediate_path);
+    /// Render cache key of a trim job: source identity, range, streams, filters and encoding
+    fn trim_cache_key(
+        &self,
+        job: &TrimJob,
+        streams: TrimStreams,
+        black_frame: (u32, u32, f64),
+        tracks: &[TrackData],
+        gap_fill: &GapFill,
+    ) -> Result<String, String> {
+        let mut parts = vec![
+            INTERMEDIATE_VIDEO_ENCODING.join(" "),
+            INTERMEDIATE_AUDIO_ENCODING.join(" "),
+            format!("{:?}", streams),
+        ];
+        match *job {
+            TrimJob::Gap(duration) => {
+                parts.push(format!("gap {:.3}s {:?} {}", duration, black_frame, gap_fill.color));
+                if let Some(slate) = &gap_fill.slate_path {
+                    parts.push(RenderCache::source_identity(slate)?);
+                }
+            }
+            TrimJob::Clip(clip) => {
+                let duration = clip.out_point - clip.in_point;
+                let (start_str, duration_str) = trim_range_args(clip.in_point, clip.out_point);
+                parts.push(RenderCache::source_identity(&clip.path)?);
+                parts.push(format!(
+                    "clip -ss {} -t {} video={} audio={}",
+                    start_str, duration_str, clip.video_enabled, clip.audio_enabled
+                ));
+                // Black frames only appear in the output for audio-only clips
+                if !clip.video_enabled {
+                    parts.push(format!("{:?}", black_frame));
+                }
+                parts.push(self.build_clip_audio_filters(clip, duration, tracks).join(","));
+                parts.push(format!("{:?}", clip.zoom_regions));
+            }
         }
+        Ok(RenderCache::key(&parts))
+    }
 
-        Ok(intermediate_files)
+    /// Where each clip of a sequential track starts in the output
+    ///
+    /// Clips keep their start_time so gaps are preserved. A clip that starts before the
+    /// previous one ends (overlap on the same track) is pushed back to the previous end.
+    pub(super) fn timeline_positions(&self, clips: &[ClipData]) -> Vec<f64> {
+        let mut positions = Vec::with_capacity(clips.len());
+        let mut previous_end = 0.0_f64;
+        for clip in clips {
+            let position = clip.start_time.max(previous_end);
+            if position > clip.start_time + MIN_GAP {
+                eprintln!("   ⚠️  Clip {} overlaps the previous clip on its track, moved to {:.3}s", clip.id, position);
+            }
+            positions.push(position);
+            previous_end = position + (clip.out_point - clip.in_point);
+        }
+        positions
+    }
+
+    /// Where each clip of a sequential track starts once its transitions are rendered
+    ///
+    /// A transition overlaps the clips it joins, so the clip after it (and everything
+    /// that follows) plays the transition's duration earlier than on the timeline.
+    pub(super) fn output_positions(&self, clips: &[ClipData], transitions: &[TransitionData]) -> Vec<f64> {
+        let mut overlap = 0.0;
+        self.timeline_positions(clips)
+            .into_iter()
+            .enumerate()
+            .map(|(index, position)| {
+                if index > 0 {
+                    if let Some(transition) = transitions::between(transitions, clips[index - 1].id, clips[index].id) {
+                        overlap += transition.duration;
+                    }
+                }
+                position - overlap
+            })
+            .collect()
+    }
+
+    /// Render a gap segment: gap_fill color (or slate image) at the sequence's frame
+    /// size and rate, plus silence when the intermediates carry audio
+    #[allow(clippy::too_many_arguments)]
+    fn render_gap(
+        &self,
+        ffmpeg: &FFmpegWrapper,
+        output_path: &Path,
+        duration: f64,
+        frame: (u32, u32, f64),
+        with_audio: bool,
+        gap_fill: &GapFill,
+        threads: usize,
+    ) -> Result<(), String> {
+        let (width, height, fps) = frame;
+        let duration_str = format!("{:.3}", duration);
+
+        let color = ffmpeg_color(&gap_fill.color)?;
+
+        let mut args: Vec<String> = Vec::new();
+        let video_filter = match &gap_fill.slate_path {
+            Some(slate) => {
+                args.extend(["-loop", "1", "-framerate"].iter().map(|s| s.to_string()));
+                args.push(fps.to_string());
+                args.push("-t".to_string());
+                args.push(duration_str.clone());
+                args.push("-i".to_string());
+                args.push(slate.clone());
+                format!(
+                    "[0:v]scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color={c},setsar=1,format=yuv420p[vout]",
+                    w = width, h = height, c = color
+                )
+            }
+            None => {
+                args.extend(["-f", "lavfi", "-i"].iter().map(|s| s.to_string()));
+                args.push(format!("color=c={}:s={}x{}:r={}:d={}", color, width, height, fps, duration_str));
+                "[0:v]format=yuv420p[vout]".to_string()
+            }
+        };
+
+        if with_audio {
+            args.extend(["-f", "lavfi", "-i"].iter().map(|s| s.to_string()));
+            args.push(format!("anullsrc=r=48000:cl=stereo:d={}", duration_str));
+        }
+
+        args.push("-filter_complex".to_string());
+        args.push(video_filter);
+        args.extend(["-map", "[vout]"].iter().chain(INTERMEDIATE_VIDEO_ENCODING).map(|s| s.to_string()));
+        if with_audio {
+            args.extend(["-map", "1:a"].iter().chain(INTERMEDIATE_AUDIO_ENCODING).map(|s| s.to_string()));
+        }
+
+        args.push("-t".to_string());
+        args.push(duration_str);
+        args.push("-threads".to_string());
+        args.push(threads.to_string());
+        args.push("-y".to_string());
+        args.push(output_path.display().to_string());
+
+        eprintln!("⬛ Gap segment {:.3}s: {:?}", duration, args);
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+        Ok(())
     }
 
     /// Trim clip with audio filtering (requires re-encoding audio)
     /// Handles audio from the source file (if present), or generates silence if missing
+    /// threads caps FFmpeg's encoder threads so concurrent trims share the CPU
+    ///
+    /// with_video / with_audio choose the streams written to the intermediate. A stream
+    /// that is wanted but disabled on the clip is filled in (black frames of black_frame
+    /// size and rate, or silence); the clip's own [0:v] / [0:a] branch is only used when
+    /// the stream is enabled.
+    #[allow(clippy::too_many_arguments)]
     fn trim_with_audio_filters(
         &self,
         ffmpeg: &FFmpegWrapper,
@@ -232,112 +1312,363 @@ impl ExportPipeline {
         start_time: f64,
         end_time: f64,
         clip: &ClipData,
+        with_video: bool,
+        with_audio: bool,
+        black_frame: (u32, u32, f64),
+        tracks: &[TrackData],
+        threads: usize,
     ) -> Result<(), String> {
         let duration = end_time - start_time;
 
-        // Build audio filter string for user-specified filters (volume, fades, mute)
-        let mut audio_filters = Vec::new();
+        // Build audio filter string for user-specified filters (volume, fades, mute)
+        let audio_filters = self.build_clip_audio_filters(clip, duration, tracks);
+
+        // Build FFmpeg command - store strings to ensure they live long enough
+        let (start_time_str, duration_str) = trim_range_args(start_time, end_time);
+        let anullsrc_spec = format!("anullsrc=r=48000:cl=stereo:d={}", duration);
+        let (black_width, black_height, black_fps) = black_frame;
+        let color_spec = format!(
+            "color=c=black:s={}x{}:r={}:d={}",
+            black_width, black_height, black_fps, duration
+        );
+
+        // Zoom regions animate the video, so they need the source size and frame rate
+        // Audio-only clips take black frames from input 2
+        let video_filter = if !with_video {
+            None
+        } else if !clip.video_enabled {
+            Some("[2:v]format=yuv420p[vout]".to_string())
+        } else if clip.zoom_regions.is_empty() {
+            Some("[0:v]null[vout]".to_string())
+        } else {
+            let metadata = ffmpeg.probe(input_path)?;
+            Some(format!(
+                "[0:v]{}[vout]",
+                self.build_zoompan_filter(&clip.zoom_regions, metadata.width, metadata.height, metadata.fps)
+            ))
+        };
+
+        // Build the audio filter chain - applies filters to audio from input 0 (if it exists)
+        // or to silence from input 1 (if input 0 has no audio or the clip is video-only)
+        let user_filters = if audio_filters.is_empty() {
+            "".to_string()
+        } else {
+            format!(",{}", audio_filters.join(","))
+        };
+        let audio_filter = |audio_input: u32| {
+            if with_audio {
+                Some(format!("[{}:a]acopy{}[aout]", audio_input, user_filters))
+            } else {
+                None
+            }
+        };
+
+        let build_args = |audio_input: u32| {
+            let filter_complex = video_filter.iter().cloned()
+                .chain(audio_filter(audio_input))
+                .collect::<Vec<_>>()
+                .join(";");
+
+            // Inputs: 0 = source clip, 1 = silence generator, 2 = black frames (audio-only clips)
+            let mut args = vec![
+                "-ss".to_string(),
+                start_time_str.clone(),
+                "-i".to_string(),
+                input_path.to_string(),
+                "-t".to_string(),
+                duration_str.clone(),
+                // Add silence generator as fallback
+                "-f".to_string(),
+                "lavfi".to_string(),
+                "-i".to_string(),
+                anullsrc_spec.clone(),
+            ];
+            if with_video && !clip.video_enabled {
+                args.extend(["-f", "lavfi", "-i", &color_spec].iter().map(|s| s.to_string()));
+            }
+            args.push("-filter_complex".to_string());
+            args.push(filter_complex);
+            if with_video {
+                args.extend(["-map", "[vout]"].iter().chain(INTERMEDIATE_VIDEO_ENCODING).map(|s| s.to_string()));
+            }
+            if with_audio {
+                args.extend(["-map", "[aout]"].iter().chain(INTERMEDIATE_AUDIO_ENCODING).map(|s| s.to_string()));
+            }
+            args.push("-threads".to_string());
+            args.push(threads.to_string());
+            args.push("-y".to_string());
+            args.push(output_path.to_string());
+            args
+        };
+
+        // Video-only clips never touch the source audio
+        let source_audio = if clip.audio_enabled { 0 } else { 1 };
+        let args = build_args(source_audio);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        // Try with original audio first
+        match ffmpeg.execute_command(&args_refs) {
+            Ok(_) => {
+                // Success with original audio
+                Ok(())
+            }
+            Err(e) if !with_audio || source_audio != 0 => Err(e),
+            Err(_) => {
+                // Input 0 has no audio - use silence from input 1
+                eprintln!("Input has no audio, using silence fallback");
+                let args = build_args(1);
+                let args_refs2: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                ffmpeg.execute_command(&args_refs2)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Build user-specified audio filters (volume, fades, mute) for a clip's audio,
+    /// followed by its track's gain and pan
+    /// duration is the length of the audio the filters are applied to
+    pub(super) fn build_clip_audio_filters(&self, clip: &ClipData, duration: f64, tracks: &[TrackData]) -> Vec<String> {
+        let mut audio_filters = Vec::new();
+
+        // Mute takes precedence
+        if clip.is_muted {
+            audio_filters.push("volume=0".to_string());
+        } else {
+            // Apply volume adjustment (100 = 1.0, 200 = 2.0, etc.)
+            if clip.volume != 100 {
+                let volume_factor = clip.volume as f64 / 100.0;
+                audio_filters.push(format!("volume={:.2}", volume_factor));
+            }
+
+            // Apply fade in
+            if clip.fade_in_duration > 0.0 {
+                audio_filters.push(format!("afade=t=in:st=0:d={:.3}", clip.fade_in_duration));
+            }
+
+            // Apply fade out
+            if clip.fade_out_duration > 0.0 {
+                let fade_out_start = duration - clip.fade_out_duration;
+                audio_filters.push(format!("afade=t=out:st={:.3}:d={:.3}", fade_out_start, clip.fade_out_duration));
+            }
+
+            audio_filters.extend(tracks::audio_filters(tracks, clip.track));
+        }
+
+        audio_filters
+    }
+
+    /// Whether a sequential track's audio has to be rendered separately from its video:
+    /// J/L cuts, or clips with audio switched off (those get no audio branch at all)
+    pub(super) fn needs_separate_audio(&self, clips: &[ClipData], transitions: &[TransitionData]) -> bool {
+        self.has_split_edits(clips, transitions) || clips.iter().any(|c| !c.audio_enabled)
+    }
+
+    /// Whether any clip or transition splits audio from video (J-cut / L-cut)
+    fn has_split_edits(&self, clips: &[ClipData], transitions: &[TransitionData]) -> bool {
+        clips.iter().any(|c| c.audio_lead != 0.0 || c.audio_lag != 0.0)
+            || transitions.iter().any(|t| t.audio_offset != 0.0)
+    }
+
+    /// Work out where each clip's audio starts and ends for sequential (concatenated) clips
+    ///
+    /// Video cuts stay where they are. A J-cut (audio_lead) starts a clip's audio early and
+    /// ends the previous clip's audio at the same point; an L-cut (audio_lag) keeps a clip's
+    /// audio playing over the start of the next clip. If both sides of a cut extend, the
+    /// overlap is mixed. A transition's audio_offset adds to the lead/lag of its clip pair.
+    fn plan_split_audio(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+    ) -> Result<Vec<SplitAudioSegment>, String> {
+        let count = clips.len();
+        let mut leads: Vec<f64> = clips.iter().map(|c| c.audio_lead).collect();
+        let mut lags: Vec<f64> = clips.iter().map(|c| c.audio_lag).collect();
+
+        for i in 0..count.saturating_sub(1) {
+            if let Some(t) = transitions::between(transitions, clips[i].id, clips[i + 1].id) {
+                if t.audio_offset > 0.0 {
+                    lags[i] += t.audio_offset;
+                } else {
+                    leads[i + 1] -= t.audio_offset;
+                }
+            }
+        }
+
+        // Video positions in the output (gaps included, transitions overlapped)
+        let video_starts = self.output_positions(clips, transitions);
+        let video_end = |i: usize| video_starts[i] + (clips[i].out_point - clips[i].in_point);
 
-        // Mute takes precedence
-        if clip.is_muted {
-            audio_filters.push("volume=0".to_string());
-        } else {
-            // Apply volume adjustment (100 = 1.0, 200 = 2.0, etc.)
-            if clip.volume != 100 {
-                let volume_factor = clip.volume as f64 / 100.0;
-                audio_filters.push(format!("volume={:.2}", volume_factor));
+        let mut segments = Vec::with_capacity(count);
+        for (i, clip) in clips.iter().enumerate() {
+            if leads[i] < 0.0 || lags[i] < 0.0 {
+                return Err(format!("Clip {} has a negative audio lead or lag", clip.id));
             }
 
-            // Apply fade in
-            if clip.fade_in_duration > 0.0 {
-                audio_filters.push(format!("afade=t=in:st=0:d={:.3}", clip.fade_in_duration));
+            let mut audio_start = video_starts[i] - leads[i];
+            if leads[i] == 0.0 && i > 0 && lags[i - 1] > 0.0 {
+                // Previous clip's L-cut plays over our first frames (if it reaches past a gap)
+                audio_start = audio_start.max(video_end(i - 1) + lags[i - 1]);
+            }
+            let mut audio_end = video_end(i) + lags[i];
+            if lags[i] == 0.0 && i + 1 < count && leads[i + 1] > 0.0 {
+                // Next clip's J-cut takes over before our last frame (if it reaches back past a gap)
+                audio_end = audio_end.min(video_starts[i + 1] - leads[i + 1]);
             }
 
-            // Apply fade out
-            if clip.fade_out_duration > 0.0 {
-                let fade_out_start = duration - clip.fade_out_duration;
-                audio_filters.push(format!("afade=t=out:st={:.3}:d={:.3}", fade_out_start, clip.fade_out_duration));
+            // Nothing to lead into before the first frame of the timeline
+            audio_start = audio_start.max(0.0);
+
+            if audio_end - audio_start <= 0.0 {
+                return Err(format!("Clip {} audio is fully covered by neighbouring J/L cuts", clip.id));
+            }
+
+            let source_start = clip.in_point + (audio_start - video_starts[i]);
+            if source_start < 0.0 {
+                return Err(format!("Clip {} audio lead extends before the start of its source", clip.id));
             }
+
+            segments.push(SplitAudioSegment {
+                source_start,
+                duration: audio_end - audio_start,
+                timeline_start: audio_start,
+            });
         }
 
-        // Build FFmpeg command - store strings to ensure they live long enough
-        let start_time_str = start_time.to_string();
-        let duration_str = duration.to_string();
-        let anullsrc_spec = format!("anullsrc=r=48000:cl=stereo:d={}", duration);
+        Ok(segments)
+    }
 
-        // Build the audio filter chain - applies filters to audio from input 0 (if it exists)
-        // or to silence from input 1 (if input 0 has no audio)
-        let user_filters = if audio_filters.is_empty() {
-            "".to_string()
-        } else {
-            format!(",{}", audio_filters.join(","))
+    /// Render the audio for sequential clips independently of the video concat
+    ///
+    /// Used for J/L cuts and video-only clips. Each clip's audio is cut from its source over
+    /// its planned range, filtered (volume, fades), delayed to its position and mixed. Clips
+    /// with audio disabled are left out entirely; clips without an audio stream contribute
+    /// silence. Gaps between clips stay silent. Output is trimmed to the video length, or
+    /// padded with silence up to end_time when that is later (multi-track timeline end).
+    ///
+    /// Example filter for two clips with a 1s J-cut:
+    /// "[0:a]aresample=48000,aformat=channel_layouts=stereo,apad,atrim=duration=4.000[sa0];[1:a]...,adelay=4000:all=1[sa1];[sa0][sa1]amix=inputs=2:duration=longest:normalize=0,apad,atrim=duration=10.000[aout]"
+    fn render_split_audio(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        tracks: &[TrackData],
+        end_time: Option<f64>,
+        output_path: &Path,
+    ) -> Result<(), String> {
+        let video_end = match (self.output_positions(clips, transitions).last(), clips.last()) {
+            (Some(position), Some(clip)) => position + (clip.out_point - clip.in_point),
+            _ => 0.0,
         };
+        let total_duration = end_time.map_or(video_end, |end| end.max(video_end));
 
-        // Build filter_complex - try to use audio from input 0, fall back to silence from input 1
-        let filter_complex_with_audio = format!(
-            "[0:a]acopy{}[aout]",
-            user_filters
-        );
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
 
-        // Build command with TWO inputs: video file and silence generator
-        let mut args = vec![
-            "-ss".to_string(),
-            start_time_str.clone(),
-            "-i".to_string(),
-            input_path.to_string(),
-            "-t".to_string(),
-            duration_str.clone(),
-            // Add silence generator as fallback
-            "-f".to_string(),
-            "lavfi".to_string(),
-            "-i".to_string(),
-            anullsrc_spec.clone(),
-            "-filter_complex".to_string(),
-            filter_complex_with_audio.clone(),
-            "-map".to_string(),
-            "0:v".to_string(),
-            "-map".to_string(),
-            "[aout]".to_string(),
-            "-c:v".to_string(),
-            "libx264".to_string(),
-            "-crf".to_string(),
-            "23".to_string(),
-            "-c:a".to_string(),
-            "aac".to_string(),
-            "-b:a".to_string(),
-            "192k".to_string(),
-            "-y".to_string(),
-            output_path.to_string(),
-        ];
+        // Missing audio stream (e.g. screen recording) -> silence
+        let has_audio = |clip: &ClipData| {
+            ffmpeg.probe(&clip.path)
+                .map(|m| m.audio_codec.is_some())
+                .unwrap_or(true)
+        };
+        let (inputs, filter_parts) = self.build_split_audio_graph(
+            clips, transitions, tracks, &has_audio, 0, total_duration, "[aout]"
+        )?;
+        let mut args: Vec<String> = inputs.into_iter().flatten().collect();
+
+        args.push("-filter_complex".to_string());
+        args.push(filter_parts.join(";"));
+        args.push("-map".to_string());
+        args.push("[aout]".to_string());
+        args.push("-c:a".to_string());
+        args.push("pcm_s16le".to_string());
+        args.push("-y".to_string());
+        args.push(output_path.display().to_string());
+
+        eprintln!("🔊 Separate audio FFmpeg arguments: {:?}", args);
 
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+        Ok(())
+    }
 
-        // Try with original audio first
-        match ffmpeg.execute_command(&args_refs) {
-            Ok(_) => {
-                // Success with original audio
-                Ok(())
+    /// Build the separate audio graph for sequential clips (see render_split_audio)
+    ///
+    /// Returns (arguments for each input, filter parts). Inputs are numbered from first_input so the
+    /// graph can be embedded in a larger one; the mix is written to output_label.
+    /// has_audio tells whether a clip's source has an audio stream.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn build_split_audio_graph(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        tracks: &[TrackData],
+        has_audio: &dyn Fn(&ClipData) -> bool,
+        first_input: usize,
+        total_duration: f64,
+        output_label: &str,
+    ) -> Result<(Vec<Vec<String>>, Vec<String>), String> {
+        let segments = self.plan_split_audio(clips, transitions)?;
+
+        let mut inputs: Vec<Vec<String>> = Vec::new();
+        let mut filter_parts = Vec::new();
+        let mut mix_inputs = String::new();
+        let mut mix_count = 0;
+
+        for (clip, segment) in clips.iter().zip(&segments) {
+            if !clip.audio_enabled {
+                continue;
             }
-            Err(_) => {
-                // Input 0 has no audio - use silence from input 1
-                eprintln!("Input has no audio, using silence fallback");
-                let filter_complex_silence = format!(
-                    "[1:a]acopy{}[aout]",
-                    user_filters
-                );
-                args[11] = filter_complex_silence;
-                let args_refs2: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-                ffmpeg.execute_command(&args_refs2)?;
-                Ok(())
+            let index = mix_count;
+            let input_index = first_input + index;
+            mix_count += 1;
+
+            inputs.push(vec![
+                "-ss".to_string(),
+                format!("{:.3}", segment.source_start),
+                "-t".to_string(),
+                format!("{:.3}", segment.duration),
+                "-i".to_string(),
+                clip.path.clone(),
+            ]);
+
+            let source = if has_audio(clip) {
+                format!("[{}:a]aresample=48000,aformat=channel_layouts=stereo,apad", input_index)
+            } else {
+                "anullsrc=r=48000:cl=stereo".to_string()
+            };
+
+            let mut chain = vec![source, format!("atrim=duration={:.3}", segment.duration)];
+            chain.extend(self.build_clip_audio_filters(clip, segment.duration, tracks));
+            let delay_ms = (segment.timeline_start * 1000.0).round() as i64;
+            if delay_ms > 0 {
+                chain.push(format!("adelay={}:all=1", delay_ms));
             }
+
+            filter_parts.push(format!("{}[sa{}]", chain.join(","), index));
+            mix_inputs.push_str(&format!("[sa{}]", index));
         }
+
+        if mix_count == 0 {
+            // Every clip is video-only: the track is silent
+            filter_parts.push(format!("anullsrc=r=48000:cl=stereo,atrim=duration={:.3}{}", total_duration, output_label));
+        } else {
+            filter_parts.push(format!(
+                "{}amix=inputs={}:duration=longest:normalize=0,apad,atrim=duration={:.3}{}",
+                mix_inputs, mix_count, total_duration, output_label
+            ));
+        }
+
+        Ok((inputs, filter_parts))
     }
 
     /// Concatenate intermediate files and re-encode with settings
+    /// If audio_track is given, it replaces the concatenated clip audio
     fn concatenate_and_encode(
         &self,
         intermediate_files: &[PathBuf],
+        audio_track: Option<&Path>,
         text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
         settings: &ExportSettings,
     ) -> Result<String, String> {
         if intermediate_files.is_empty() {
@@ -362,7 +1693,8 @@ impl ExportPipeline {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let concat_file_path = std::env::temp_dir().join("clipforge_concat_list.txt");
+        // The list goes next to the intermediates, inside the export's workspace
+        let concat_file_path = intermediate_files[0].with_file_name("concat_list.txt");
         std::fs::write(&concat_file_path, concat_list)
             .map_err(|e| format!("Failed to write concat list: {}", e))?;
 
@@ -376,6 +1708,16 @@ impl ExportPipeline {
             "-i", concat_list_str,
         ];
 
+        // Separately rendered audio (J/L cuts) comes in as a second input
+        let audio_track_str;
+        let mut audio_map = "0:a:0";
+        if let Some(audio_path) = audio_track {
+            audio_track_str = audio_path.display().to_string();
+            args.push("-i");
+            args.push(&audio_track_str);
+            audio_map = "1:a:0";
+        }
+
         // Build video filter with scaling and text overlays
         let mut vf_chain = Vec::new();
 
@@ -385,17 +1727,27 @@ impl ExportPipeline {
             vf_chain.push(scale);
         }
 
-        // Add text overlay filters if there are any overlays
+        // Build text overlay filters separately - they are drawn after redactions
+        // so captions are never blurred out
+        let mut text_chain = Vec::new();
         for overlay in text_overlays {
-            if let Ok(drawtext_filter) = self.build_drawtext_filter(overlay) {
-                eprintln!("   Adding drawtext filter: {}", drawtext_filter);
-                vf_chain.push(drawtext_filter);
-            }
+            let drawtext_filter = self.build_drawtext_filter(overlay)?;
+            eprintln!("   Adding drawtext filter: {}", drawtext_filter);
+            text_chain.push(drawtext_filter);
         }
 
         // Apply the complete filter chain if there are any filters
         let vf_filter_str;
-        if !vf_chain.is_empty() {
+        let mut video_map = "0:v:0";
+        if !redactions.is_empty() {
+            // Redactions need split/overlay with labelled pads, so use filter_complex
+            vf_filter_str = self.build_finishing_graph("[0:v]", text_overlays, redactions, settings)?;
+            eprintln!("📊 SINGLE-TRACK: Complete filter graph: {}", vf_filter_str);
+            args.push("-filter_complex");
+            args.push(&vf_filter_str);
+            video_map = "[vout]";
+        } else if !vf_chain.is_empty() || !text_chain.is_empty() {
+            vf_chain.extend(text_chain);
             vf_filter_str = vf_chain.join(",");
             eprintln!("📊 SINGLE-TRACK: Complete filter chain: {}", vf_filter_str);
             args.push("-vf");
@@ -404,6 +1756,14 @@ impl ExportPipeline {
             eprintln!("   ⚠️  No filters to apply!");
         }
 
+        // Master bus on the final mix (the mapped audio stream is the whole timeline mix)
+        let master_chain = settings.master.filter_chain();
+        if let Some(chain) = &master_chain {
+            eprintln!("   Adding master bus: {}", chain);
+            args.push("-af");
+            args.push(chain);
+        }
+
         eprintln!("📊 SINGLE-TRACK: FFmpeg arguments before encoding: {:?}", args);
 
         // Debug: Print the exact filter string if it exists
@@ -419,8 +1779,8 @@ impl ExportPipeline {
         // Add explicit stream mapping and encoding settings
         // This ensures video and audio are properly selected from the concat demuxer
         args.extend(&[
-            "-map", "0:v:0",   // Map video stream 0 from concat output
-            "-map", "0:a:0",   // Map audio stream 0 from concat output
+            "-map", video_map, // Map video stream 0 from concat output (or filtered graph output)
+            "-map", audio_map, // Map audio stream 0 from concat output (or split audio track)
             "-c:v", "libx264",
             "-crf", "23",           // High quality H.264
             "-c:a", "aac",
@@ -450,13 +1810,173 @@ impl ExportPipeline {
         Ok(settings.output_path.clone())
     }
 
-    /// Concatenate intermediate files with transitions using xfade filter and apply text overlays
+    /// Finishing filter graph for the joined timeline: scale, redactions, then text
+    /// Reads the input pad (e.g. [0:v]) and writes [vout]; text is drawn last so captions
+    /// are never blurred out
+    fn build_finishing_graph(
+        &self,
+        input: &str,
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        settings: &ExportSettings,
+    ) -> Result<String, String> {
+        let base_chain = settings.resolution.scale_filter().unwrap_or_else(|| "null".to_string());
+        let mut graph_parts = vec![format!("{}{}[vbase]", input, base_chain)];
+
+        let mut text_input = "[vbase]";
+        if !redactions.is_empty() {
+            graph_parts.extend(self.build_redaction_filter(redactions, "[vbase]", "[vredacted]")?);
+            text_input = "[vredacted]";
+        }
+
+        let text_chain = text_overlays.iter()
+            .map(|overlay| self.build_drawtext_filter(overlay))
+            .collect::<Result<Vec<_>, _>>()?;
+        let text_filter = if text_chain.is_empty() { "null".to_string() } else { text_chain.join(",") };
+        graph_parts.push(format!("{}{}[vout]", text_input, text_filter));
+
+        Ok(graph_parts.join(";"))
+    }
+
+    /// Encode the concatenated timeline as chunks running in parallel, then stitch them
+    ///
+    /// Chunks end on cut points (whole intermediates, see chunks::plan_chunks) and are
+    /// encoded video-only with identical encoder settings and closed GOPs, so the concat
+    /// demuxer joins them with stream copy. The audio is mixed in one pass over the whole
+    /// timeline, which keeps the master bus continuous and avoids AAC priming gaps at the
+    /// chunk boundaries. A timeline that makes a single chunk is encoded in one pass.
+    /// Chunks are planned from the rendered files' durations; dry runs use planned_durations.
+    /// Lists, chunks and the audio mix are written next to the intermediates, so the
+    /// export's JobWorkspace removes them.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_chunked(
+        &self,
+        intermediate_files: &[PathBuf],
+        planned_durations: &[f64],
+        audio_track: Option<&Path>,
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        settings: &ExportSettings,
+        chunk_duration: f64,
+    ) -> Result<String, String> {
+        if intermediate_files.is_empty() {
+            return Err("No intermediate files to concatenate".to_string());
+        }
+
+        // Chunks encode concurrently, so work on a copy instead of holding the lock
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        ffmpeg.set_progress(40.0, "Planning chunks...".to_string(), None);
+        let durations = if ffmpeg.is_recording() {
+            planned_durations.to_vec()
+        } else {
+            intermediate_files.iter()
+                .map(|path| ffmpeg.probe(&path.display().to_string()).map(|metadata| metadata.duration))
+                .collect::<Result<Vec<f64>, String>>()?
+        };
+        let chunks = chunks::plan_chunks(&durations, chunk_duration);
+        if chunks.len() < 2 {
+            eprintln!("   Timeline fits in one chunk, encoding in one pass");
+            return self.concatenate_and_encode(intermediate_files, audio_track, text_overlays, redactions, settings);
+        }
+        eprintln!("🧩 Encoding {} chunks of about {:.0}s", chunks.len(), chunk_duration);
+
+        // Phase 1: the whole audio mix (with master bus) in one pass, kept uncompressed until the final mux
+        ffmpeg.set_progress(42.0, "Mixing audio...".to_string(), None);
+        let audio_path = intermediate_files[0].with_file_name("chunked_audio.wav");
+        let audio_list_path = intermediate_files[0].with_file_name("audio_concat_list.txt");
+        let audio_input = match audio_track {
+            Some(track) => track.display().to_string(),
+            None => {
+                write_concat_list(intermediate_files, &audio_list_path)?;
+                audio_list_path.display().to_string()
+            }
+        };
+        let audio_output = audio_path.display().to_string();
+        let mut audio_args: Vec<&str> = Vec::new();
+        if audio_track.is_none() {
+            audio_args.extend(["-f", "concat", "-safe", "0"]);
+        }
+        audio_args.extend(["-i", &audio_input, "-map", "0:a:0", "-vn"]);
+        let master_chain = settings.master.filter_chain();
+        if let Some(chain) = &master_chain {
+            eprintln!("   Adding master bus: {}", chain);
+            audio_args.extend(["-af", chain]);
+        }
+        audio_args.extend(["-c:a", "pcm_s16le", "-y", &audio_output]);
+        ffmpeg.execute_command(&audio_args)?;
+
+        // Phase 2: video chunks on a worker pool
+        let pool = WorkerPool::new(settings.parallelism);
+        let total = chunks.len();
+        let chunk_files = pool.run(
+            total,
+            |index| {
+                let chunk = &chunks[index];
+                let list_path = intermediate_files[0].with_file_name(format!("chunk_{}_list.txt", index));
+                let output_path = intermediate_files[0].with_file_name(format!("chunk_{}.mp4", index));
+                write_concat_list(&intermediate_files[chunk.files.clone()], &list_path)?;
+
+                // Overlays and redactions are re-timed, as each chunk starts at t = 0
+                let graph = self.build_finishing_graph(
+                    "[0:v]",
+                    &chunks::text_overlays_for(chunk, text_overlays),
+                    &chunks::redactions_for(chunk, redactions),
+                    settings,
+                )?;
+                let list = list_path.display().to_string();
+                let output = output_path.display().to_string();
+                let threads = pool.threads_per_job.to_string();
+                eprintln!("   Chunk {}: {:.2}s-{:.2}s ({} segments)", index, chunk.start, chunk.start + chunk.duration, chunk.files.len());
+
+                ffmpeg.execute_command(&[
+                    "-f", "concat",
+                    "-safe", "0",
+                    "-i", &list,
+                    "-filter_complex", &graph,
+                    "-map", "[vout]",
+                    "-an",
+                    "-c:v", "libx264",
+                    "-crf", "23",
+                    "-flags", "+cgop",      // Closed GOPs: no frame references across the chunk boundary
+                    "-threads", &threads,
+                    "-y",
+                    &output,
+                ])?;
+                Ok(output_path)
+            },
+            |done| {
+                let percentage = 45.0 + done as f64 / total as f64 * 45.0;
+                ffmpeg.set_progress(percentage, format!("Encoded chunk {} of {}", done, total), None);
+            },
+        )?;
+
+        // Phase 3: stitch the chunks with stream copy and add the audio
+        ffmpeg.set_progress(92.0, "Stitching chunks...".to_string(), None);
+        let video_path = intermediate_files[0].with_file_name("chunked_video.mp4");
+        self.concatenate_only(&chunk_files, &video_path)?;
+        self.replace_audio(&video_path, &audio_path, Path::new(&settings.output_path))?;
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(settings.output_path.clone())
+    }
+
+    /// Join intermediate files with xfade/acrossfade at each transition (concat elsewhere),
+    /// then scale, redact, caption and encode as concatenate_and_encode does
+    ///
+    /// segments describe the intermediates in order, with [N:v]/[N:a] pads for input N.
+    /// If audio_track is given, it replaces the clip audio (segments then carry no audio).
+    #[allow(clippy::too_many_arguments)]
     fn concatenate_with_transitions(
         &self,
         intermediate_files: &[PathBuf],
-        clips: &[ClipData],
+        segments: &[TrackSegment],
+        audio_track: Option<&Path>,
         transitions: &[TransitionData],
         text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
         settings: &ExportSettings,
     ) -> Result<String, String> {
         if intermediate_files.is_empty() {
@@ -466,279 +1986,71 @@ impl ExportPipeline {
         let ffmpeg = self.ffmpeg.lock()
             .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
 
-        // Update progress
         ffmpeg.set_progress(40.0, "Building transition filters...".to_string(), None);
 
-        // Build FFmpeg command with all clips as inputs
         let mut args: Vec<String> = Vec::new();
         for file in intermediate_files {
             args.push("-i".to_string());
             args.push(file.display().to_string());
         }
 
-        // Build filter_complex with xfade filters for video and concat for audio
-        let (mut video_filter, audio_filter) = self.build_xfade_and_audio_filter(clips, transitions, intermediate_files.len())?;
-
-        // Add scaling to the video filter (text overlays disabled - see .claude/PR-STRETCH-009-NOTES.md)
-        let mut additional_filters = Vec::new();
-
-        // Add scaling if needed
-        if let Some(scale) = settings.resolution.scale_filter() {
-            additional_filters.push(scale);
-        }
-
-        // Text overlay export disabled - feature suspended pending FFmpeg limitations resolution
-        // text_overlays are passed but intentionally not processed
-
-        // Append additional filters to video filter chain
-        if !additional_filters.is_empty() {
-            video_filter.push(',');
-            video_filter.push_str(&additional_filters.join(","));
-        }
+        let mut graph_parts = transitions::join_segments(segments, transitions, "[vjoined]", "[ajoined]")?;
+        graph_parts.push(self.build_finishing_graph("[vjoined]", text_overlays, redactions, settings)?);
 
-        // Combine video and audio filters
-        let filter_complex = if audio_filter.is_empty() {
-            video_filter
-        } else {
-            format!("{};{}", video_filter, audio_filter)
+        // Separately rendered audio (J/L cuts) is the input after the intermediates
+        let audio_input = match audio_track {
+            Some(audio_path) => {
+                args.push("-i".to_string());
+                args.push(audio_path.display().to_string());
+                format!("[{}:a]", intermediate_files.len())
+            }
+            None => "[ajoined]".to_string(),
         };
+        let master_chain = settings.master.filter_chain().unwrap_or_else(|| "anull".to_string());
+        graph_parts.push(format!("{}{}[aout]", audio_input, master_chain));
 
-        args.push("-filter_complex".to_string());
-        args.push(filter_complex);
-        args.push("-map".to_string());
-        args.push("[vout]".to_string());  // Map final video output
-        args.push("-map".to_string());
-        args.push("[aout]".to_string());  // Map final audio output
-
-        eprintln!("📊 WITH-TRANSITIONS: FFmpeg arguments before encoding: {:?}", args);
-
-        // Add encoding settings
-        args.push("-c:v".to_string());
-        args.push("libx264".to_string());
-        args.push("-crf".to_string());
-        args.push("23".to_string());
-        args.push("-c:a".to_string());
-        args.push("aac".to_string());
-        args.push("-b:a".to_string());
-        args.push("192k".to_string());
-        args.push("-y".to_string());
-        args.push(settings.output_path.clone());
+        let filter_complex = graph_parts.join(";");
+        eprintln!("📊 WITH-TRANSITIONS: Complete filter graph: {}", filter_complex);
 
-        eprintln!("📊 WITH-TRANSITIONS: Final FFmpeg arguments: {:?}", args);
+        args.extend([
+            "-filter_complex".to_string(),
+            filter_complex,
+            "-map".to_string(),
+            "[vout]".to_string(),
+            "-map".to_string(),
+            "[aout]".to_string(),
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-crf".to_string(),
+            "23".to_string(),
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-b:a".to_string(),
+            "192k".to_string(),
+            "-y".to_string(),
+            settings.output_path.clone(),
+        ]);
 
-        // Convert to &str refs
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-        // Update progress
         ffmpeg.set_progress(50.0, "Rendering transitions...".to_string(), None);
-
-        // Execute FFmpeg
         let result = ffmpeg.execute_command(&args_refs);
-
-        // Update progress
         ffmpeg.set_progress(95.0, "Finalizing...".to_string(), None);
 
         result?;
 
-        // Complete!
         ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
         Ok(settings.output_path.clone())
     }
 
-    /// Build FFmpeg filter_complex string with xfade transitions for video and concat for audio
-    ///
-    /// Returns (video_filter, audio_filter)
-    ///
-    /// Example video filter for 3 clips with 2 transitions:
-    /// "[0:v][1:v]xfade=transition=fade:duration=1:offset=5[v01];[v01][2:v]xfade=transition=wipeleft:duration=1.5:offset=12[vout]"
-    ///
-    /// Example audio filter:
-    /// "[0:a][1:a][2:a]concat=n=3:v=0:a=1[aout]"
-    fn build_xfade_and_audio_filter(
-        &self,
-        clips: &[ClipData],
-        transitions: &[TransitionData],
-        num_clips: usize,
-    ) -> Result<(String, String), String> {
-        if num_clips < 2 {
-            return Ok((
-                "[0:v]copy[vout]".to_string(),
-                "[0:a]acopy[aout]".to_string()
-            ));
-        }
-
-        // Build a map of transitions by clip pairs
-        let mut transition_map: std::collections::HashMap<(u32, u32), &TransitionData> = std::collections::HashMap::new();
-        for transition in transitions {
-            transition_map.insert((transition.clip_id_before, transition.clip_id_after), transition);
-        }
-
-        // First, normalize all video streams to same frame rate, resolution, and timebase for xfade
-        // Find max resolution to scale all clips to
-        let max_width = clips.iter().map(|c| 1280).max().unwrap_or(1280);
-        let max_height = clips.iter().map(|c| 720).max().unwrap_or(720);
-
-        let mut normalized_streams = Vec::new();
-        for i in 0..num_clips {
-            let normalized_label = format!("[v{}n]", i);
-            // Scale to common resolution, normalize format/fps/timebase
-            let fps_filter = format!(
-                "[{}:v]scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:-1:-1:color=black,format=yuv420p,fps=fps=30:round=near,settb=expr=1/30,setpts=PTS-STARTPTS{}",
-                i, max_width, max_height, max_width, max_height, normalized_label
-            );
-            normalized_streams.push(fps_filter);
-        }
-
-        let mut video_filter_parts = normalized_streams;
-        let mut current_offset = 0.0;
-
-        // Process each pair of clips for video xfade
-        for i in 0..num_clips - 1 {
-            let clip_before = &clips[i];
-            let clip_after = &clips[i + 1];
-
-            // Get duration of current clip (before transition)
-            let clip_duration = clip_before.out_point - clip_before.in_point;
-
-            // Check if there's a transition between these clips
-            let transition = transition_map.get(&(clip_before.id, clip_after.id));
-
-            // Input labels (use normalized streams)
-            let input_before = if i == 0 {
-                "[v0n]".to_string()
-            } else {
-                format!("[v{}]", i - 1)
-            };
-            let input_after = format!("[v{}n]", i + 1);
-
-            // Output label
-            let output = if i == num_clips - 2 {
-                "[vout]".to_string()
-            } else {
-                format!("[v{}]", i)
-            };
-
-            if let Some(trans) = transition {
-                // There's a transition: use xfade
-                let xfade_type = self.map_transition_type(&trans.transition_type)?;
-                let duration = trans.duration;
-
-                // Offset is when the transition starts (clip duration - transition duration)
-                let offset = current_offset + clip_duration - duration;
-
-                let xfade_filter = format!(
-                    "{}{}xfade=transition={}:duration={:.3}:offset={:.3}{}",
-                    input_before,
-                    input_after,
-                    xfade_type,
-                    duration,
-                    offset,
-                    output
-                );
-
-                video_filter_parts.push(xfade_filter);
-
-                // Update offset: add current clip duration minus overlap
-                current_offset += clip_duration - duration;
-            } else {
-                // No transition: use very short xfade (0.01s) to simulate hard cut
-                // This keeps the filter chain consistent
-                let duration = 0.01;
-                let offset = current_offset + clip_duration - duration;
-
-                let xfade_filter = format!(
-                    "{}{}xfade=transition=fade:duration={:.3}:offset={:.3}{}",
-                    input_before,
-                    input_after,
-                    duration,
-                    offset,
-                    output
-                );
-
-                video_filter_parts.push(xfade_filter);
-                current_offset += clip_duration - duration;
-            }
-        }
-
-        // Build audio filter: trim and delay audio to match xfade video timing
-        // When xfade creates an overlap, we need to adjust audio timing accordingly
-        let mut audio_filter_parts = Vec::new();
-        let mut audio_time = 0.0;
-
-        for i in 0..num_clips {
-            let clip = &clips[i];
-            let clip_duration = clip.out_point - clip.in_point;
-
-            // Get transition info for this clip (if transitioning to next)
-            let has_transition = if i < num_clips - 1 {
-                let next_clip = &clips[i + 1];
-                transitions.iter().any(|t| t.clip_id_before == clip.id && t.clip_id_after == next_clip.id)
-            } else {
-                false
-            };
-
-            let transition_duration = if has_transition {
-                transitions.iter()
-                    .find(|t| t.clip_id_before == clip.id && i < num_clips - 1 && t.clip_id_after == clips[i + 1].id)
-                    .map(|t| t.duration)
-                    .unwrap_or(0.0)
-            } else {
-                0.0
-            };
-
-            // For this clip: trim audio duration and delay it to match timeline position
-            let audio_duration = clip_duration;
-            let delay_ms = (audio_time * 1000.0) as i64;
-
-            let audio_part = if delay_ms > 0 {
-                format!("[{}:a?]atrim=duration={:.3},adelay={}ms[a{}]", i, audio_duration, delay_ms, i)
-            } else {
-                format!("[{}:a?]atrim=duration={:.3}[a{}]", i, audio_duration, i)
-            };
-
-            audio_filter_parts.push(audio_part);
-
-            // Update audio_time for next clip: current duration minus transition overlap
-            if has_transition && transition_duration > 0.0 {
-                audio_time += clip_duration - transition_duration;
-            } else {
-                audio_time += clip_duration - 0.01; // Account for minimal xfade even without transition
-            }
-        }
-
-        // Combine all audio streams with amix (volume normalization)
-        let audio_filter = if num_clips > 0 {
-            let audio_labels: Vec<String> = (0..num_clips).map(|i| format!("[a{}]", i)).collect();
-            format!(
-                "{}amix=inputs={}:duration=first[aout]",
-                audio_filter_parts.join(";"),
-                num_clips
-            )
-        } else {
-            String::new()
-        };
-
-        Ok((video_filter_parts.join(";"), audio_filter))
-    }
-
-    /// Map frontend transition type to FFmpeg xfade transition name
-    fn map_transition_type(&self, transition_type: &str) -> Result<String, String> {
-        match transition_type {
-            "fade" => Ok("fade".to_string()),
-            "crossfade" | "dissolve" => Ok("fade".to_string()),  // Same in FFmpeg
-            "fadeToBlack" => Ok("fadeblack".to_string()),  // FFmpeg has fadeblack
-            "wipeLeft" => Ok("wiperight".to_string()),  // Wipe left = new clip comes from left
-            "wipeRight" => Ok("wipeleft".to_string()),  // Wipe right = new clip comes from right
-            _ => Err(format!("Unsupported transition type: {}", transition_type))
-        }
-    }
-
     /// Export multi-track timeline with overlays
     fn export_multitrack(
         &self,
         clips: Vec<ClipData>,
         transitions: Vec<TransitionData>,
         text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: &[TrackData],
         settings: ExportSettings,
     ) -> Result<String, String> {
         // Group clips by track
@@ -750,36 +2062,51 @@ impl ExportPipeline {
             a.start_time.partial_cmp(&b.start_time)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
+        // Overlays composite in track order (track 2 above track 1), then by timeline position
         overlay_clips.sort_by(|a, b| {
-            a.start_time.partial_cmp(&b.start_time)
-                .unwrap_or(std::cmp::Ordering::Equal)
+            a.track.cmp(&b.track).then(
+                a.start_time.partial_cmp(&b.start_time)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            )
         });
 
         if track0_clips.is_empty() {
             return Err("Multi-track export requires at least one clip on track 0 (base)".to_string());
         }
 
-        // Create temp directory
-        let temp_dir = std::env::temp_dir().join("clipforge_export");
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+        // The output runs to the end of the last clip on any track; the base track is
+        // extended with a gap segment when an overlay ends after it
+        let base_end = match (self.timeline_positions(&track0_clips).last(), track0_clips.last()) {
+            (Some(position), Some(clip)) => position + (clip.out_point - clip.in_point),
+            _ => 0.0,
+        };
+        let timeline_end = overlay_clips.iter()
+            .map(|c| c.start_time + (c.out_point - c.in_point))
+            .fold(base_end, f64::max);
+        eprintln!("   Timeline extent: {:.3}s (base track ends at {:.3}s)", timeline_end, base_end);
+
+        // Intermediate files live in this export's own workspace (removed when it is dropped)
+        let workspace = JobWorkspace::create("export")?;
+        let temp_dir = workspace.path();
+
+        // Filter transitions to get only track0 transitions
+        let track0_clip_ids: std::collections::HashSet<u32> = track0_clips.iter().map(|c| c.id).collect();
+        let track0_transitions: Vec<TransitionData> = transitions.into_iter()
+            .filter(|t| track0_clip_ids.contains(&t.clip_id_before) && track0_clip_ids.contains(&t.clip_id_after))
+            .collect();
+        let base_separate_audio = self.needs_separate_audio(&track0_clips, &track0_transitions);
+        let base_streams = if base_separate_audio { TrimStreams::SequentialVideo } else { TrimStreams::Sequential };
 
         // Phase 1: Process track 0 (base) - trim and concatenate to single base video
         // Progress: 0-30% for trimming base clips
-        let track0_intermediates = self.trim_clips(&track0_clips, &temp_dir)?;
-        let base_video_path = temp_dir.join("base_video.mp4");
+        let track0_intermediates = self.trim_clips(&track0_clips, temp_dir, base_streams, tracks, &settings, Some(timeline_end))?;
+        let mut base_video_path = temp_dir.join("base_video.mp4");
 
         // Update progress: concatenating base video
         if let Ok(ffmpeg) = self.ffmpeg.lock() {
             ffmpeg.set_progress(30.0, "Concatenating base track...".to_string(), None);
         }
 
-        // Filter transitions to get only track0 transitions
-        let track0_clip_ids: std::collections::HashSet<u32> = track0_clips.iter().map(|c| c.id).collect();
-        let track0_transitions: Vec<TransitionData> = transitions.into_iter()
-            .filter(|t| track0_clip_ids.contains(&t.clip_id_before) && track0_clip_ids.contains(&t.clip_id_after))
-            .collect();
-
         // Concatenate track 0 clips into base video with jump cuts
         // Transitions disabled - using only jump cuts
         self.concatenate_only(&track0_intermediates, &base_video_path)?;
@@ -789,12 +2116,24 @@ impl ExportPipeline {
             let _ = std::fs::remove_file(file);
         }
 
+        // J/L cuts or video-only clips on the base track: add separately rendered audio
+        if base_separate_audio {
+            let split_audio_path = temp_dir.join("base_split_audio.wav");
+            let remuxed_path = temp_dir.join("base_video_split.mp4");
+            let result = self.render_split_audio(&track0_clips, &track0_transitions, tracks, Some(timeline_end), &split_audio_path)
+                .and_then(|_| self.replace_audio(&base_video_path, &split_audio_path, &remuxed_path));
+            let _ = std::fs::remove_file(&split_audio_path);
+            let _ = std::fs::remove_file(&base_video_path);
+            result?;
+            base_video_path = remuxed_path;
+        }
+
         // Phase 2: Trim overlay clips
         // Progress: 40-60% for trimming overlay clips
         if let Ok(ffmpeg) = self.ffmpeg.lock() {
             ffmpeg.set_progress(40.0, "Processing overlay clips...".to_string(), None);
         }
-        let overlay_intermediates = self.trim_clips(&overlay_clips, &temp_dir)?;
+        let overlay_intermediates = self.trim_clips(&overlay_clips, temp_dir, TrimStreams::Enabled, tracks, &settings, None)?;
 
         // Phase 3: Build overlay filter and execute FFmpeg
         // Progress: 60-100% for applying overlays
@@ -806,6 +2145,7 @@ impl ExportPipeline {
             &overlay_intermediates,
             &overlay_clips,
             &text_overlays,
+            &redactions,
             &settings,
         );
 
@@ -814,7 +2154,6 @@ impl ExportPipeline {
         for file in &overlay_intermediates {
             let _ = std::fs::remove_file(file);
         }
-        let _ = std::fs::remove_dir(&temp_dir);
 
         result
     }
@@ -844,7 +2183,7 @@ impl ExportPipeline {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let concat_file_path = std::env::temp_dir().join("clipforge_base_concat_list.txt");
+        let concat_file_path = intermediate_files[0].with_file_name("base_concat_list.txt");
         std::fs::write(&concat_file_path, concat_list)
             .map_err(|e| format!("Failed to write concat list: {}", e))?;
 
@@ -871,210 +2210,36 @@ impl ExportPipeline {
         Ok(())
     }
 
-    /// Concatenate base track clips with transitions for multi-track export
-    fn concatenate_base_with_transitions(
+    /// Replace a video's audio with another audio file (video stream copied)
+    fn replace_audio(
         &self,
-        intermediate_files: &[PathBuf],
-        clips: &[ClipData],
-        transitions: &[TransitionData],
+        video_path: &Path,
+        audio_path: &Path,
         output_path: &Path,
     ) -> Result<(), String> {
-        if intermediate_files.is_empty() {
-            return Err("No files to concatenate".to_string());
-        }
-
         let ffmpeg = self.ffmpeg.lock()
             .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?;
 
-        // Build FFmpeg command with individual inputs for both video and audio
-        let mut args: Vec<String> = Vec::new();
-
-        // Add individual video inputs
-        for file in intermediate_files {
-            args.push("-i".to_string());
-            args.push(file.display().to_string());
-        }
-
-        // Build filter_complex with xfade and acrossfade filters
-        // Note: video inputs start at 0
-        let (combined_filter, _) = self.build_xfade_and_audio_filter_offset(clips, transitions, intermediate_files.len(), 0)?;
-
-        args.push("-filter_complex".to_string());
-        args.push(combined_filter);
-        args.push("-map".to_string());
-        args.push("[vout]".to_string());
-        args.push("-map".to_string());
-        args.push("[aout]".to_string());  // Audio from acrossfade filter
-
-        // Use libx264 for encoding
-        args.push("-c:v".to_string());
-        args.push("libx264".to_string());
-        args.push("-crf".to_string());
-        args.push("23".to_string());
-        args.push("-c:a".to_string());
-        args.push("aac".to_string());
-        args.push("-b:a".to_string());
-        args.push("192k".to_string());
-        args.push("-y".to_string());
-        args.push(output_path.display().to_string());
+        let video_str = video_path.display().to_string();
+        let audio_str = audio_path.display().to_string();
+        let output_str = output_path.display().to_string();
 
-        // Convert to &str refs
-        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let args = vec![
+            "-i", &video_str,
+            "-i", &audio_str,
+            "-map", "0:v:0",
+            "-map", "1:a:0",
+            "-c:v", "copy",
+            "-c:a", "aac",
+            "-b:a", "192k",
+            "-y",
+            &output_str,
+        ];
 
-        ffmpeg.execute_command(&args_refs)?;
+        ffmpeg.execute_command(&args)?;
         Ok(())
     }
 
-    /// Build xfade filter with input offset (for when concat audio is input 0)
-    fn build_xfade_and_audio_filter_offset(
-        &self,
-        clips: &[ClipData],
-        transitions: &[TransitionData],
-        num_clips: usize,
-        input_offset: usize,
-    ) -> Result<(String, String), String> {
-        if num_clips < 2 {
-            return Ok((
-                format!("[{}:v]copy[vout]", input_offset),
-                format!("[{}:a?]anull[aout]", input_offset)
-            ));
-        }
-
-        // Build a map of transitions by clip pairs
-        let mut transition_map: std::collections::HashMap<(u32, u32), &TransitionData> = std::collections::HashMap::new();
-        for transition in transitions {
-            transition_map.insert((transition.clip_id_before, transition.clip_id_after), transition);
-        }
-
-        // First, normalize all video streams to same frame rate, resolution, and timebase for xfade
-        // Find max resolution to scale all clips to
-        let max_width = clips.iter().map(|c| 1280).max().unwrap_or(1280);  // Default to 1280 if unknown
-        let max_height = clips.iter().map(|c| 720).max().unwrap_or(720);   // Default to 720 if unknown
-
-        let mut normalized_streams = Vec::new();
-        for i in 0..num_clips {
-            let input_idx = i + input_offset;
-            let normalized_label = format!("[v{}n]", i);
-            // Scale to common resolution, normalize format/fps/timebase
-            let fps_filter = format!(
-                "[{}:v]scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:-1:-1:color=black,format=yuv420p,fps=fps=30:round=near,settb=expr=1/30,setpts=PTS-STARTPTS{}",
-                input_idx, max_width, max_height, max_width, max_height, normalized_label
-            );
-            normalized_streams.push(fps_filter);
-        }
-
-        let mut video_filter_parts = normalized_streams;
-        let mut audio_filter_parts = Vec::new();
-        let mut current_offset = 0.0;
-
-        // Process each pair of clips for video xfade and audio acrossfade
-        for i in 0..num_clips - 1 {
-            let clip_before = &clips[i];
-            let clip_after = &clips[i + 1];
-
-            let clip_duration = clip_before.out_point - clip_before.in_point;
-            let transition = transition_map.get(&(clip_before.id, clip_after.id));
-
-            // Video inputs
-            let input_before = if i == 0 {
-                "[v0n]".to_string()
-            } else {
-                format!("[v{}]", i - 1)
-            };
-            let input_after = format!("[v{}n]", i + 1);
-
-            // Audio inputs - use a?to allow missing audio, which FFmpeg will handle
-            // by not creating an audio stream if the input has no audio
-            // For acrossfade, if either input has no audio, we just copy the available audio
-            let audio_input_before = if i == 0 {
-                format!("[{}:a?]", input_offset)
-            } else {
-                format!("[a{}]", i - 1)
-            };
-            let audio_input_after = format!("[{}:a?]", i + 1 + input_offset);
-
-            // Output labels
-            let video_output = if i == num_clips - 2 {
-                "[vout]".to_string()
-            } else {
-                format!("[v{}]", i)
-            };
-            let audio_output = if i == num_clips - 2 {
-                "[aout]".to_string()
-            } else {
-                format!("[a{}]", i)
-            };
-
-            if let Some(trans) = transition {
-                // Video xfade
-                let xfade_type = self.map_transition_type(&trans.transition_type)?;
-                let duration = trans.duration;
-                let offset = current_offset + clip_duration - duration;
-
-                let xfade_filter = format!(
-                    "{}{}xfade=transition={}:duration={:.3}:offset={:.3}{}",
-                    input_before,
-                    input_after,
-                    xfade_type,
-                    duration,
-                    offset,
-                    video_output
-                );
-                video_filter_parts.push(xfade_filter);
-
-                // Audio acrossfade (match video timing)
-                // If audio streams are missing, this will fail, so we try acrossfade first,
-                // and if that fails, the filter_complex will error out and export will fail
-                // User should note that clips with no audio will cause issues
-                let acrossfade_filter = format!(
-                    "{}{}acrossfade=d={:.3}:c1=tri:c2=tri{}",
-                    audio_input_before,
-                    audio_input_after,
-                    duration,
-                    audio_output
-                );
-                audio_filter_parts.push(acrossfade_filter);
-
-                current_offset += clip_duration - duration;
-            } else {
-                // No transition: use very short xfade (0.01s) to simulate hard cut
-                let duration = 0.01;
-                let offset = current_offset + clip_duration - duration;
-
-                let xfade_filter = format!(
-                    "{}{}xfade=transition=fade:duration={:.3}:offset={:.3}{}",
-                    input_before,
-                    input_after,
-                    duration,
-                    offset,
-                    video_output
-                );
-                video_filter_parts.push(xfade_filter);
-
-                // Audio acrossfade (short crossfade for continuity)
-                let acrossfade_filter = format!(
-                    "{}{}acrossfade=d={:.3}:c1=tri:c2=tri{}",
-                    audio_input_before,
-                    audio_input_after,
-                    duration,
-                    audio_output
-                );
-                audio_filter_parts.push(acrossfade_filter);
-
-                current_offset += clip_duration - duration;
-            }
-        }
-
-        // Combine video and audio filters
-        let combined_filter = if !audio_filter_parts.is_empty() {
-            format!("{};{}", video_filter_parts.join(";"), audio_filter_parts.join(";"))
-        } else {
-            video_filter_parts.join(";")
-        };
-
-        Ok((combined_filter, String::new()))
-    }
-
     /// Apply overlay clips on top of base video using FFmpeg filter_complex, and apply text overlays
     fn apply_overlays(
         &self,
@@ -1082,6 +2247,7 @@ impl ExportPipeline {
         overlay_files: &[PathBuf],
         overlay_clips: &[ClipData],
         text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
         settings: &ExportSettings,
     ) -> Result<String, String> {
         eprintln!("🎬 apply_overlays: {} video overlays, {} text overlays", overlay_files.len(), text_overlays.len());
@@ -1103,34 +2269,70 @@ impl ExportPipeline {
         }
 
         // Build filter_complex string (video overlays + audio mixing)
-        let (video_filter, audio_filter) = self.build_overlay_and_audio_filter(overlay_clips, overlay_files.len());
+        // Tiled layouts need a canvas: the export resolution, or the base video's own size
+        let canvas = if settings.layout.is_tiled() {
+            match settings.resolution.dimensions() {
+                Some(dimensions) => dimensions,
+                None => {
+                    // A dry run never renders the base video, so there is nothing to measure
+                    if ffmpeg.is_recording() {
+                        return Err("Dry runs of tiled layouts need an export resolution".to_string());
+                    }
+                    let metadata = ffmpeg.probe(&base_video.display().to_string())?;
+                    (metadata.width, metadata.height)
+                }
+            }
+        } else {
+            (0, 0)
+        };
+        eprintln!("   Layout: {:?} on {}x{} canvas", settings.layout, canvas.0, canvas.1);
+
+        // Input N+1 is overlay clip N
+        let overlay_labels: Vec<(String, String)> = (1..=overlay_clips.len())
+            .map(|input_index| (format!("[{}:v]", input_index), format!("[{}:a]", input_index)))
+            .collect();
+        let (mut video_filter, mut audio_filter) = self.build_overlay_and_audio_filter(
+            ("[0:v]", "[0:a]"), &overlay_labels, overlay_clips, settings.layout, canvas
+        )?;
+
+        // Master bus runs on [aout] after all tracks are mixed
+        if let Some(chain) = settings.master.filter_chain() {
+            if let Some(mix) = audio_filter.strip_suffix("[aout]") {
+                audio_filter = format!("{}[amixed];[amixed]{}[aout]", mix, chain);
+            }
+        }
+
+        // Redactions are applied after compositing so they also cover picture-in-picture content
+        let mut video_map = "[out]".to_string();
+        if !redactions.is_empty() {
+            let redaction_parts = self.build_redaction_filter(redactions, "[out]", "[outr]")?;
+            video_filter = format!("{};{}", video_filter, redaction_parts.join(";"));
+            video_map = "[outr]".to_string();
+        }
+
+        // Text is drawn last so captions are never blurred out
+        if !text_overlays.is_empty() {
+            let text_chain = text_overlays.iter()
+                .map(|overlay| self.build_drawtext_filter(overlay))
+                .collect::<Result<Vec<_>, _>>()?;
+            video_filter = format!("{};{}{}[outt]", video_filter, video_map, text_chain.join(","));
+            video_map = "[outt]".to_string();
+        }
 
         // Combine video and audio filters
-        let mut filter_complex = if audio_filter.is_empty() {
+        let filter_complex = if audio_filter.is_empty() {
             video_filter
         } else {
             format!("{};{}", video_filter, audio_filter)
         };
 
-        // Text overlay export disabled - feature suspended pending FFmpeg limitations resolution
-        // text_overlays parameter is accepted but not processed (see .claude/PR-STRETCH-009-NOTES.md)
-        if !text_overlays.is_empty() {
-            eprintln!("   ⚠️  {} text overlays present but not exported (feature suspended)", text_overlays.len());
-        }
-
         args.push("-filter_complex".to_string());
         args.push(filter_complex);
         args.push("-map".to_string());
-        args.push("[out]".to_string());
+        args.push(video_map);
         args.push("-map".to_string());
         args.push("[aout]".to_string());  // Audio mixed from all sources
 
-        // Add scaling filter if needed (applied to final output)
-        if let Some(scale) = settings.resolution.scale_filter() {
-            // Scale already applied via filter_complex if needed
-            // For now, skip separate scaling (filter_complex handles it)
-        }
-
         // Add encoding settings
         args.push("-c:v".to_string());
         args.push("libx264".to_string());
@@ -1158,59 +2360,126 @@ impl ExportPipeline {
 
     /// Build FFmpeg filter_complex string for overlaying multiple clips and mixing audio
     ///
+    /// Placement follows the layout template. Picture-in-picture keeps the base video
+    /// untouched and puts overlays in the bottom-right corner; tiled layouts scale the
+    /// base video and each overlay track into their slot on a black canvas.
+    /// Overlay clips are shifted to their timeline position so they play from their first frame,
+    /// and their audio is delayed to the same position before mixing.
+    /// Audio-only clips get no [N:v] branch and video-only clips no [N:a] branch.
+    /// Overlays are composited in the order given, so later clips land on top; callers
+    /// pass them sorted by track for z-order.
+    ///
+    /// base_labels are the base track's (video, audio) pads and overlay_labels the pads of
+    /// each overlay clip, e.g. ("[1:v]", "[1:a]") when overlay clips are inputs 1..N.
+    ///
     /// Returns (video_filter, audio_filter)
     ///
-    /// Example video output:
-    /// "[1:v]scale=iw*0.25:-1[scaled1];[0:v][scaled1]overlay=W-w-20:H-h-20:enable='between(t,5.0,10.0)'[temp1];..."
+    /// Example video output (picture-in-picture):
+    /// "[1:v]setpts=PTS-STARTPTS+5.000/TB,scale=iw*0.25:-1[scaled1];[0:v][scaled1]overlay=W-w-20:H-h-20:enable='between(t,5.000,10.000)'[out]"
+    ///
+    /// Example video output (side-by-side on 1280x720):
+    /// "[0:v]scale=640:720:force_original_aspect_ratio=decrease,pad=1280:720:0+(640-iw)/2:0+(720-ih)/2:color=black[base];[1:v]...[scaled1];[base][scaled1]overlay=640:0:enable='between(t,0.000,8.000)'[out]"
     ///
     /// Example audio output:
-    /// "[0:a][1:a]amix=inputs=2:duration=first[aout]"
-    fn build_overlay_and_audio_filter(&self, overlay_clips: &[ClipData], num_overlays: usize) -> (String, String) {
+    /// "[1:a]adelay=5000:all=1[adelayed1];[0:a][adelayed1]amix=inputs=2:duration=first:normalize=0[aout]"
+    pub(super) fn build_overlay_and_audio_filter(
+        &self,
+        base_labels: (&str, &str),
+        overlay_labels: &[(String, String)],
+        overlay_clips: &[ClipData],
+        layout: LayoutTemplate,
+        canvas: (u32, u32),
+    ) -> Result<(String, String), String> {
         let mut filter_parts = Vec::new();
+        let (canvas_width, canvas_height) = canvas;
+        let (base_video, base_audio) = base_labels;
+        if overlay_labels.len() != overlay_clips.len() {
+            return Err("Overlay labels don't match overlay clips".to_string());
+        }
 
-        // First, scale all overlays to 25% width (matching preview)
-        for (index, _clip) in overlay_clips.iter().enumerate() {
-            let input_index = index + 1; // Input 0 is base, overlays start at 1
-            let scale_filter = format!(
-                "[{}:v]scale=iw*0.25:-1[scaled{}]",
-                input_index,
-                input_index
-            );
-            filter_parts.push(scale_filter);
+        // Overlay clip N is numbered N+1 in pad names; only clips with video are composited
+        let video_overlays: Vec<(usize, &ClipData)> = overlay_clips.iter()
+            .enumerate()
+            .filter(|(_, c)| c.video_enabled)
+            .map(|(index, c)| (index + 1, c))
+            .collect();
+
+        // Each distinct overlay track with video gets its own slot; slot 0 is the base track
+        let mut overlay_tracks: Vec<u32> = video_overlays.iter().map(|(_, c)| c.track).collect();
+        overlay_tracks.sort();
+        overlay_tracks.dedup();
+
+        if let Some(max_tracks) = layout.max_tracks() {
+            if overlay_tracks.len() + 1 > max_tracks {
+                return Err(format!(
+                    "Layout {:?} supports at most {} concurrent tracks, timeline has {}",
+                    layout, max_tracks, overlay_tracks.len() + 1
+                ));
+            }
         }
 
-        // Then, overlay each scaled clip
-        for (index, clip) in overlay_clips.iter().enumerate() {
-            let input_index = index + 1; // Input 0 is base, overlays start at 1
+        let slot_for = |slot_index: usize| {
+            layout.slot(slot_index, canvas_width, canvas_height)
+                .ok_or_else(|| format!("Layout {:?} has no slot {}", layout, slot_index))
+        };
+
+        // Base video: untouched for picture-in-picture, fitted into slot 0 otherwise
+        let mut current_label = if layout.is_tiled() {
+            let slot = slot_for(0)?;
+            let base_label = if video_overlays.is_empty() { "[out]" } else { "[base]" };
+            filter_parts.push(format!(
+                "{}scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:{}+({}-iw)/2:{}+({}-ih)/2:color=black{}",
+                base_video, slot.width, slot.height, canvas_width, canvas_height,
+                slot.x, slot.width, slot.y, slot.height, base_label
+            ));
+            base_label.to_string()
+        } else if video_overlays.is_empty() {
+            filter_parts.push(format!("{}null[out]", base_video));
+            "[out]".to_string()
+        } else {
+            base_video.to_string()
+        };
+
+        for (index, &(input_index, clip)) in video_overlays.iter().enumerate() {
             let start = clip.start_time;
             let duration = clip.out_point - clip.in_point;
             let end = start + duration;
 
-            // Input labels
-            let input_label = if index == 0 {
-                "[0:v]".to_string()  // First overlay uses base video
-            } else {
-                format!("[temp{}]", index)  // Subsequent overlays use previous output
-            };
-
-            let output_label = if index == num_overlays - 1 {
+            let output_label = if index == video_overlays.len() - 1 {
                 "[out]".to_string()  // Last overlay outputs to [out]
             } else {
                 format!("[temp{}]", index + 1)
             };
 
-            // Overlay filter: scale to 25% and position in bottom-right corner with 20px padding
+            // Shift the overlay so its first frame lands at its timeline position
+            let shift = format!("setpts=PTS-STARTPTS+{:.3}/TB", start);
+
             // enable filter makes overlay appear only during its timeline duration
-            let overlay_filter = format!(
-                "{}[scaled{}]overlay=W-w-20:H-h-20:enable='between(t,{:.3},{:.3})'{}",
-                input_label,
-                input_index,
-                start,
-                end,
-                output_label
-            );
+            let overlay_filter = if layout.is_tiled() {
+                let slot_index = 1 + overlay_tracks.iter().position(|&t| t == clip.track).unwrap_or(0);
+                let slot = slot_for(slot_index)?;
+                filter_parts.push(format!(
+                    "{}{},scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:-1:-1:color=black[scaled{}]",
+                    overlay_labels[input_index - 1].0, shift, slot.width, slot.height, slot.width, slot.height, input_index
+                ));
+                format!(
+                    "{}[scaled{}]overlay={}:{}:enable='between(t,{:.3},{:.3})'{}",
+                    current_label, input_index, slot.x, slot.y, start, end, output_label
+                )
+            } else {
+                // Scale to 25% width (matching preview) and position in bottom-right corner with 20px padding
+                filter_parts.push(format!(
+                    "{}{},scale=iw*0.25:-1[scaled{}]",
+                    overlay_labels[input_index - 1].0, shift, input_index
+                ));
+                format!(
+                    "{}[scaled{}]overlay=W-w-20:H-h-20:enable='between(t,{:.3},{:.3})'{}",
+                    current_label, input_index, start, end, output_label
+                )
+            };
 
             filter_parts.push(overlay_filter);
+            current_label = output_label;
         }
 
         let video_filter = filter_parts.join(";");
@@ -1223,25 +2492,240 @@ impl ExportPipeline {
 
         // Use amix to combine all audio inputs
         // amix will mix whatever audio streams are available
-        let audio_filter = if overlay_clips.is_empty() {
-            // No overlays: just use base audio (which may or may not exist)
-            "[0:a]acopy[aout]".to_string()
+        let audio_overlays: Vec<(usize, &ClipData)> = overlay_clips.iter()
+            .enumerate()
+            .filter(|(_, c)| c.audio_enabled)
+            .map(|(index, c)| (index + 1, c))
+            .collect();
+
+        let audio_filter = if audio_overlays.is_empty() {
+            // No overlay audio: just use base audio (which may or may not exist)
+            format!("{}acopy[aout]", base_audio)
         } else {
-            // With overlays: mix all available audio inputs using amix
-            // amix will handle clips with or without audio gracefully
-            let num_inputs = overlay_clips.len() + 1;
-            let mut input_labels = "[0:a]".to_string();
-            for i in 1..num_inputs {
-                input_labels.push_str(&format!("[{}:a]", i));
+            // With overlays: delay each overlay's audio to its timeline position, then mix
+            let mut audio_parts = Vec::new();
+            let mut input_labels = base_audio.to_string();
+            for &(input_index, clip) in &audio_overlays {
+                let delay_ms = (clip.start_time * 1000.0).round() as i64;
+                audio_parts.push(format!(
+                    "{}adelay={}:all=1[adelayed{}]",
+                    overlay_labels[input_index - 1].1, delay_ms, input_index
+                ));
+                input_labels.push_str(&format!("[adelayed{}]", input_index));
             }
             // amix: mix inputs together (output will have audio from all sources)
-            format!("{}amix=inputs={}[aout]", input_labels, num_inputs)
+            // normalize=0 sums at unity so levels don't drop as tracks are added; gain is set per track
+            // duration=first: the base track spans the whole timeline, so it sets the length
+            audio_parts.push(format!("{}amix=inputs={}:duration=first:normalize=0[aout]", input_labels, audio_overlays.len() + 1));
+            audio_parts.join(";")
         };
 
-        (video_filter, audio_filter)
+        Ok((video_filter, audio_filter))
+    }
+
+    /// Validate a clip's zoom regions: inside the clip, inside the frame, and not overlapping
+    fn validate_zoom_regions(&self, clip: &ClipData) -> Result<(), String> {
+        let clip_duration = clip.out_point - clip.in_point;
+        let mut regions: Vec<&ZoomRegionData> = clip.zoom_regions.iter().collect();
+        regions.sort_by(|a, b| {
+            a.start_time.partial_cmp(&b.start_time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut previous_end = 0.0;
+        for region in regions {
+            if region.start_time < previous_end || region.duration <= 0.0
+                || region.start_time + region.duration > clip_duration + 0.001
+            {
+                return Err(format!("Clip {} has an invalid or overlapping zoom region", clip.id));
+            }
+            if region.ease_in < 0.0 || region.ease_out < 0.0
+                || region.ease_in + region.ease_out > region.duration
+            {
+                return Err(format!("Clip {} has zoom easing longer than the zoom region", clip.id));
+            }
+            if region.width <= 0.0 || region.height <= 0.0
+                || region.x < 0.0 || region.y < 0.0
+                || region.x + region.width > 100.0
+                || region.y + region.height > 100.0
+            {
+                return Err(format!("Clip {} has a zoom target outside the frame", clip.id));
+            }
+            previous_end = region.start_time + region.duration;
+        }
+        Ok(())
+    }
+
+    /// Build an animated zoompan filter for a clip's zoom regions
+    ///
+    /// Each region contributes a progress curve p(t) that eases 0 -> 1 over ease_in,
+    /// holds at 1, and eases back to 0 over ease_out (smoothstep). Zoom and pan center
+    /// are interpolated from the full frame to the target rectangle by p(t). Regions
+    /// don't overlap, so their curves can simply be summed.
+    ///
+    /// Example for one region zooming 2x into the top-left quarter:
+    /// "zoompan=z='1+1.0000*(<p>)':x='...':y='...':d=1:s=1920x1080:fps=30.000"
+    pub(super) fn build_zoompan_filter(&self, regions: &[ZoomRegionData], width: u32, height: u32, fps: f64) -> String {
+        let mut zoom_terms = Vec::new();
+        let mut center_x_terms = Vec::new();
+        let mut center_y_terms = Vec::new();
+
+        for region in regions {
+            let start = region.start_time;
+            let end = region.start_time + region.duration;
+            let hold_start = start + region.ease_in;
+            let hold_end = end - region.ease_out;
+
+            // Smoothstep ease: u*u*(3-2*u)
+            let ease_in = if region.ease_in > 0.0 {
+                format!("(((it-{s:.3})/{d:.3})*((it-{s:.3})/{d:.3})*(3-2*((it-{s:.3})/{d:.3})))", s = start, d = region.ease_in)
+            } else {
+                "1".to_string()
+            };
+            let ease_out = if region.ease_out > 0.0 {
+                format!("((({e:.3}-it)/{d:.3})*(({e:.3}-it)/{d:.3})*(3-2*(({e:.3}-it)/{d:.3})))", e = end, d = region.ease_out)
+            } else {
+                "1".to_string()
+            };
+            let progress = format!(
+                "if(between(it,{:.3},{:.3}),{},if(between(it,{:.3},{:.3}),1,if(between(it,{:.3},{:.3}),{},0)))",
+                start, hold_start, ease_in,
+                hold_start, hold_end,
+                hold_end, end, ease_out
+            );
+
+            // Zoom so the whole target rectangle fits (zoompan caps zoom at 10)
+            let target_zoom = (100.0 / region.width).min(100.0 / region.height).min(10.0);
+            let target_center_x = (region.x + region.width / 2.0) / 100.0;
+            let target_center_y = (region.y + region.height / 2.0) / 100.0;
+
+            zoom_terms.push(format!("{:.4}*{}", target_zoom - 1.0, progress));
+            center_x_terms.push(format!("{:.4}*{}", target_center_x - 0.5, progress));
+            center_y_terms.push(format!("{:.4}*{}", target_center_y - 0.5, progress));
+        }
+
+        let zoom_expr = format!("1+{}", zoom_terms.join("+"));
+        let center_x = format!("(0.5+{})", center_x_terms.join("+"));
+        let center_y = format!("(0.5+{})", center_y_terms.join("+"));
+
+        // Keep the visible window inside the frame while panning
+        format!(
+            "zoompan=z='{}':x='clip({}*iw-iw/zoom/2,0,iw-iw/zoom)':y='clip({}*ih-ih/zoom/2,0,ih-ih/zoom)':d=1:s={}x{}:fps={:.3}",
+            zoom_expr, center_x, center_y, width, height, fps
+        )
+    }
+
+    /// Validate a redaction region before building filters for it
+    fn validate_redaction(&self, redaction: &RedactionData) -> Result<(), String> {
+        if redaction.duration <= 0.0 || redaction.start_time < 0.0 {
+            return Err(format!("Redaction {} has invalid timing", redaction.id));
+        }
+        if redaction.width <= 0.0 || redaction.height <= 0.0 {
+            return Err(format!("Redaction {} has an empty region", redaction.id));
+        }
+        if redaction.x < 0.0 || redaction.y < 0.0
+            || redaction.x + redaction.width > 100.0
+            || redaction.y + redaction.height > 100.0
+        {
+            return Err(format!("Redaction {} extends outside the frame", redaction.id));
+        }
+        if redaction.mode != RedactionMode::Box && redaction.strength == 0 {
+            return Err(format!("Redaction {} has zero strength", redaction.id));
+        }
+        if redaction.mode == RedactionMode::Box {
+            ffmpeg_color(&redaction.color).map_err(|e| format!("Redaction {}: {}", redaction.id, e))?;
+        }
+        Ok(())
+    }
+
+    /// Build filter_complex parts that apply redaction regions to a video stream
+    ///
+    /// Blur and pixelate regions are cropped out of the frame, processed, and overlaid back
+    /// in place only during their time range. Box regions are painted with drawbox.
+    /// Regions are chained, so the last part writes to output_label.
+    ///
+    /// Example for one blur region:
+    /// "[vin]split=2[rbase0][rsrc0];[rsrc0]crop=w=iw*20/100:h=ih*10/100:x=iw*5/100:y=ih*5/100,boxblur=...[rfx0];[rbase0][rfx0]overlay=x=main_w*5/100:y=main_h*5/100:enable='between(t,2.000,6.000)'[vout]"
+    pub(super) fn build_redaction_filter(
+        &self,
+        redactions: &[RedactionData],
+        input_label: &str,
+        output_label: &str,
+    ) -> Result<Vec<String>, String> {
+        let mut filter_parts = Vec::new();
+
+        for (index, redaction) in redactions.iter().enumerate() {
+            let input = if index == 0 {
+                input_label.to_string()
+            } else {
+                format!("[rout{}]", index - 1)
+            };
+            let output = if index == redactions.len() - 1 {
+                output_label.to_string()
+            } else {
+                format!("[rout{}]", index)
+            };
+
+            let enable = format!(
+                "enable='between(t,{:.3},{:.3})'",
+                redaction.start_time,
+                redaction.start_time + redaction.duration
+            );
+
+            if redaction.mode == RedactionMode::Box {
+                let color = ffmpeg_color(&redaction.color)?;
+                filter_parts.push(format!(
+                    "{}drawbox=x=iw*{}/100:y=ih*{}/100:w=iw*{}/100:h=ih*{}/100:color={}:t=fill:{}{}",
+                    input, redaction.x, redaction.y, redaction.width, redaction.height, color, enable, output
+                ));
+                continue;
+            }
+
+            // Radius/block size is clamped to the region so tiny regions don't fail filter init
+            let effect = match redaction.mode {
+                RedactionMode::Pixelate => format!(
+                    "pixelize=w={}:h={}",
+                    redaction.strength, redaction.strength
+                ),
+                _ => format!(
+                    "boxblur=luma_radius='min({},floor(min(w,h)/2))':luma_power=2:chroma_radius='min({},floor(min(cw,ch)/2))':chroma_power=2",
+                    redaction.strength, redaction.strength
+                ),
+            };
+
+            filter_parts.push(format!("{}split=2[rbase{}][rsrc{}]", input, index, index));
+            filter_parts.push(format!(
+                "[rsrc{}]crop=w=iw*{}/100:h=ih*{}/100:x=iw*{}/100:y=ih*{}/100,{}[rfx{}]",
+                index, redaction.width, redaction.height, redaction.x, redaction.y, effect, index
+            ));
+            filter_parts.push(format!(
+                "[rbase{}][rfx{}]overlay=x=main_w*{}/100:y=main_h*{}/100:{}{}",
+                index, index, redaction.x, redaction.y, enable, output
+            ));
+        }
+
+        Ok(filter_parts)
+    }
+
+    /// Validate a text overlay before building its drawtext filter
+    fn validate_text_overlay(&self, overlay: &TextOverlayData) -> Result<(), String> {
+        if overlay.duration <= 0.0 || overlay.start_time < 0.0 {
+            return Err(format!("Text overlay {} has invalid timing", overlay.id));
+        }
+        if overlay.text.is_empty() {
+            return Err(format!("Text overlay {} has no text", overlay.id));
+        }
+        if overlay.font_size == 0 {
+            return Err(format!("Text overlay {} has zero font size", overlay.id));
+        }
+        if !(0.0..=100.0).contains(&overlay.x) || !(0.0..=100.0).contains(&overlay.y) {
+            return Err(format!("Text overlay {} is positioned outside the frame", overlay.id));
+        }
+        ffmpeg_color(&overlay.color).map_err(|e| format!("Text overlay {}: {}", overlay.id, e))?;
+        Ok(())
     }
 
-    /// Build FFmpeg drawtext filter for a text overlay
+    /// Build FFmpeg drawtext filter for a text overlay (validated by validate_text_overlay)
     ///
     /// Format: drawtext=text='...':x='(main_w*x)/100':y='(main_h*y)/100':fontsize=N:fontcolor=0xRRGGBB
     ///
@@ -1249,7 +2733,7 @@ impl ExportPipeline {
     /// - Within single quotes, to include a literal single quote: end quote, escaped quote, start quote: '...' '\'' '...'
     /// - This becomes: ...\...
     /// - But in practice, FFmpeg also accepts \' inside single quotes for escaping
-    fn build_drawtext_filter(&self, overlay: &TextOverlayData) -> Result<String, String> {
+    pub(super) fn build_drawtext_filter(&self, overlay: &TextOverlayData) -> Result<String, String> {
         // Escape special characters in text for FFmpeg drawtext filter
         // FFmpeg drawtext filter documentation:
         // - For text parameter, we can use single quotes to protect most special chars
@@ -1264,12 +2748,7 @@ impl ExportPipeline {
             .replace("\\", "\\\\")                    // Backslashes -> double backslash
             .replace("'", "'\\''");                   // Single quote -> '\'(escaped quote)'
 
-        // Convert color from hex (#RRGGBB) - FFmpeg accepts hex colors with 0x prefix
-        let fontcolor = if overlay.color.starts_with('#') {
-            format!("0x{}", &overlay.color[1..])  // Convert #RRGGBB to 0xRRGGBB
-        } else {
-            overlay.color.clone()
-        };
+        let fontcolor = ffmpeg_color(&overlay.color)?;
 
         // Calculate x and y from percentages (0-100) to pixel positions
         // Use main_w and main_h for width/height in FFmpeg expressions
@@ -1297,3 +2776,259 @@ impl ExportPipeline {
         Ok(filter)
     }
 }
+
+/// Write a concat demuxer list of files (single quotes in paths escaped)
+fn write_concat_list(files: &[PathBuf], list_path: &Path) -> Result<(), String> {
+    let concat_list = files
+        .iter()
+        .map(|p| format!("file '{}'", p.display().to_string().replace("'", "'\\''")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(list_path, concat_list)
+        .map_err(|e| format!("Failed to write concat list: {}", e))
+}
+
+/// Convert a #RRGGBB color to FFmpeg's 0xRRGGBB form
+/// Anything else is rejected, as the value is spliced into filter and lavfi strings
+pub(super) fn ffmpeg_color(color: &str) -> Result<String, String> {
+    match color.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(format!("0x{}", hex)),
+        _ => Err(format!("Invalid color {} (expected #RRGGBB)", color)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline() -> ExportPipeline {
+        ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())))
+    }
+
+    fn inputs(count: usize) -> Vec<(String, String)> {
+        (1..=count).map(|i| (format!("[{}:v]", i), format!("[{}:a]", i))).collect()
+    }
+
+    fn transition(before: u32, after: u32, audio_offset: f64) -> TransitionData {
+        TransitionData {
+            id: 1,
+            clip_id_before: before,
+            clip_id_after: after,
+            transition_type: "fade".to_string(),
+            duration: 0.5,
+            options: TransitionOptions::default(),
+            audio_offset,
+        }
+    }
+
+    fn segment(source_start: f64, duration: f64, timeline_start: f64) -> SplitAudioSegment {
+        SplitAudioSegment { source_start, duration, timeline_start }
+    }
+
+    // Two back-to-back clips: 4s from 2s into the first source, then 3s from 1s into the second
+    fn cut_clips() -> Vec<ClipData> {
+        vec![ClipData::fixture(1, 0.0, 2.0, 6.0), ClipData::fixture(2, 4.0, 1.0, 4.0)]
+    }
+
+    #[test]
+    fn test_split_audio_lead_on_first_clip_is_clamped() {
+        let mut clips = cut_clips();
+        clips[0].audio_lead = 1.0;
+
+        // Nothing comes before the first frame, so the lead can't start the audio early
+        let segments = pipeline().plan_split_audio(&clips, &[]).unwrap();
+        assert_eq!(segments, vec![segment(2.0, 4.0, 0.0), segment(1.0, 3.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_split_audio_lag_on_last_clip() {
+        let mut clips = cut_clips();
+        clips[1].audio_lag = 1.0;
+
+        // The last clip's audio runs past its video; render_split_audio trims it to the video length
+        let segments = pipeline().plan_split_audio(&clips, &[]).unwrap();
+        assert_eq!(segments, vec![segment(2.0, 4.0, 0.0), segment(1.0, 4.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_split_audio_offset_across_transition() {
+        let clips = cut_clips();
+
+        // The 0.5s fade overlaps the clips, so the second clip's video starts at 3.5s
+
+        // Negative offset: the second clip's audio leads in half a second before its video
+        let j_cut = pipeline().plan_split_audio(&clips, &[transition(1, 2, -0.5)]).unwrap();
+        assert_eq!(j_cut, vec![segment(2.0, 3.0, 0.0), segment(0.5, 3.5, 3.0)]);
+
+        // Positive offset: the first clip's audio lags half a second past its video
+        let l_cut = pipeline().plan_split_audio(&clips, &[transition(1, 2, 0.5)]).unwrap();
+        assert_eq!(l_cut, vec![segment(2.0, 4.5, 0.0), segment(2.0, 2.0, 4.5)]);
+
+        // The offset only applies to the clip pair it joins
+        let unrelated = pipeline().plan_split_audio(&clips, &[transition(2, 1, 0.5)]).unwrap();
+        assert_eq!(unrelated, vec![segment(2.0, 4.0, 0.0), segment(1.0, 3.0, 4.0)]);
+    }
+
+    #[test]
+    fn test_split_audio_rejects_lead_before_source_start() {
+        let mut clips = cut_clips();
+        clips[1].audio_lead = 1.5;
+
+        // The second clip's source starts at 1s, so it only has 1s of audio before its first frame
+        assert!(pipeline().plan_split_audio(&clips, &[]).is_err());
+    }
+
+    #[test]
+    fn test_smart_render_respects_an_explicit_renderer() {
+        // Smart render is on by default, but must not override a requested single-pass render
+        let settings = ExportSettings {
+            renderer: Renderer::SinglePass,
+            ..ExportSettings::new(Resolution::Source, "/tmp/out.mp4".to_string())
+        };
+        let reason = pipeline().plan_smart_render(&[ClipData::fixture(1, 0.0, 0.0, 2.0)], &[], &[], &[], &[], &settings).unwrap_err();
+        assert!(reason.contains("single-pass"));
+    }
+
+    #[test]
+    fn test_transitions_must_join_neighbouring_clips() {
+        let pipeline = pipeline();
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 2.0),
+            ClipData::fixture(2, 2.0, 0.0, 2.0),
+            ClipData::fixture(3, 5.0, 0.0, 2.0),
+        ];
+        assert!(pipeline.validate_transitions(&clips, &[transition(1, 2, 0.0)]).is_ok());
+
+        // Clip 3 isn't right after clip 1, and there's a gap between clips 2 and 3
+        assert!(pipeline.validate_transitions(&clips, &[transition(1, 3, 0.0)]).is_err());
+        assert!(pipeline.validate_transitions(&clips, &[transition(2, 3, 0.0)]).is_err());
+
+        let too_long = TransitionData { duration: 2.5, ..transition(1, 2, 0.0) };
+        assert!(pipeline.validate_transitions(&clips, &[too_long]).is_err());
+
+        // Picture-in-picture timelines have no route that renders transitions
+        let overlapping = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData::fixture(2, 2.0, 0.0, 2.0), ClipData { track: 1, ..ClipData::fixture(3, 1.0, 0.0, 2.0) }];
+        assert!(pipeline.validate_transitions(&overlapping, &[transition(1, 2, 0.0)]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_text_overlays_are_rejected() {
+        let overlay = TextOverlayData {
+            id: 1,
+            text: "Intro".to_string(),
+            start_time: 0.5,
+            duration: 2.0,
+            x: 50.0,
+            y: 90.0,
+            font_size: 48,
+            font_family: "Arial".to_string(),
+            color: "#FFFFFF".to_string(),
+            animation: "none".to_string(),
+        };
+        assert!(pipeline().validate_text_overlay(&overlay).is_ok());
+
+        // Rejected before any renderer runs, instead of being dropped by one and failing another
+        let invalid = [
+            TextOverlayData { text: String::new(), ..overlay.clone() },
+            TextOverlayData { duration: 0.0, ..overlay.clone() },
+            TextOverlayData { font_size: 0, ..overlay.clone() },
+            TextOverlayData { x: 120.0, ..overlay.clone() },
+            TextOverlayData { color: "white".to_string(), ..overlay.clone() },
+        ];
+        for overlay in &invalid {
+            assert!(pipeline().validate_text_overlay(overlay).is_err(), "{:?} should be rejected", overlay);
+        }
+    }
+
+    #[test]
+    fn test_colors_are_validated_before_use_in_filters() {
+        assert_eq!(ffmpeg_color("#20a0FF"), Ok("0x20a0FF".to_string()));
+
+        // Anything that could break out of a filter option is rejected
+        for color in ["#12345", "#1234567", "202020", "#00:00:00", "#0000,0", "#GGGGGG", "red"] {
+            assert!(ffmpeg_color(color).is_err(), "{} should be rejected", color);
+        }
+    }
+
+    #[test]
+    fn test_trim_cache_key_matches_seek_precision() {
+        let source = std::env::temp_dir().join(format!("clipforge_key_test_{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&source, b"source").unwrap();
+        let key = |in_point: f64| {
+            let clip = ClipData { path: source.display().to_string(), ..ClipData::fixture(1, 0.0, in_point, 2.0) };
+            pipeline()
+                .trim_cache_key(&TrimJob::Clip(&clip), TrimStreams::Sequential, (1280, 720, 30.0), &[], &GapFill::default())
+                .unwrap()
+        };
+
+        // FFmpeg seeks to the full-precision value, so sub-millisecond differences are different renders
+        assert_eq!(key(0.5), key(0.5));
+        assert_ne!(key(0.5), key(0.5004));
+        assert_eq!(trim_range_args(0.5004, 2.0), ("0.5004".to_string(), (2.0 - 0.5004).to_string()));
+
+        let _ = std::fs::remove_file(&source);
+    }
+
+    #[test]
+    fn test_chunk_duration_is_rejected_where_unsupported() {
+        let source = std::env::temp_dir().join(format!("clipforge_chunk_test_{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&source, b"source").unwrap();
+        let clip = |id: u32, track: u32, start_time: f64| ClipData {
+            path: source.display().to_string(),
+            track,
+            ..ClipData::fixture(id, start_time, 0.0, 2.0)
+        };
+        let chunked = |settings: ExportSettings| ExportSettings { chunk_duration: Some(1.0), ..settings };
+        let settings = chunked(ExportSettings::new(Resolution::Source, "/tmp/out.mp4".to_string()));
+
+        // Rejected before anything is probed or rendered
+        let render = |clips: Vec<ClipData>, settings: ExportSettings| {
+            pipeline().render_timeline(clips, vec![], vec![], vec![], vec![], settings).unwrap_err()
+        };
+        let single_pass = ExportSettings { renderer: Renderer::SinglePass, ..settings.clone() };
+        assert!(render(vec![clip(1, 0, 0.0)], single_pass).contains("single-pass"));
+        assert!(render(vec![clip(1, 0, 0.0), clip(2, 1, 0.5)], settings.clone()).contains("overlapping tracks"));
+        let with_transition = pipeline()
+            .render_timeline(vec![clip(1, 0, 0.0), clip(2, 0, 2.0)], vec![transition(1, 2, 0.0)], vec![], vec![], vec![], settings.clone())
+            .unwrap_err();
+        assert!(with_transition.contains("transitions"));
+
+        // Smart render (on by default) steps aside for an explicit chunked encode
+        let smart = pipeline().plan_smart_render(&[clip(1, 0, 0.0)], &[], &[], &[], &[], &settings).unwrap_err();
+        assert!(smart.contains("chunked"));
+
+        let _ = std::fs::remove_file(&source);
+    }
+
+    #[test]
+    fn test_overlays_play_from_their_timeline_position() {
+        // Overlay inputs start at their own first frame, so without the shift a clip placed
+        // at 5s would show its 5s-10s instead of 0s-5s, and its audio would start at 0s
+        let overlays = vec![ClipData { track: 1, ..ClipData::fixture(2, 5.0, 2.0, 7.0) }];
+        let (video, audio) = pipeline()
+            .build_overlay_and_audio_filter(("[0:v]", "[0:a]"), &inputs(1), &overlays, LayoutTemplate::default(), (0, 0))
+            .unwrap();
+
+        assert_eq!(
+            video,
+            "[1:v]setpts=PTS-STARTPTS+5.000/TB,scale=iw*0.25:-1[scaled1];\
+             [0:v][scaled1]overlay=W-w-20:H-h-20:enable='between(t,5.000,10.000)'[out]"
+        );
+        assert_eq!(
+            audio,
+            "[1:a]adelay=5000:all=1[adelayed1];[0:a][adelayed1]amix=inputs=2:duration=first:normalize=0[aout]"
+        );
+    }
+
+    #[test]
+    fn test_tiled_overlays_are_shifted_into_their_slot() {
+        let overlays = vec![ClipData { track: 1, ..ClipData::fixture(2, 1.5, 0.0, 3.0) }];
+        let (video, audio) = pipeline()
+            .build_overlay_and_audio_filter(("[0:v]", "[0:a]"), &inputs(1), &overlays, LayoutTemplate::SideBySide, (1280, 720))
+            .unwrap();
+
+        assert!(video.contains("[1:v]setpts=PTS-STARTPTS+1.500/TB,scale=640:720:"));
+        assert!(video.ends_with("[base][scaled1]overlay=640:0:enable='between(t,1.500,4.500)'[out]"));
+        assert!(audio.starts_with("[1:a]adelay=1500:all=1[adelayed1];"));
+    }
+}
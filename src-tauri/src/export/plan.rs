@@ -0,0 +1,162 @@
+// Render plans: what an export would run, produced by a dry run
+//
+// The pipeline runs as usual with a recording FFmpeg wrapper (see ffmpeg::recorder), so
+// the plan holds exactly the commands a real export issues. Plans can be written as a
+// POSIX shell script that reproduces the render without ClipForge.
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::recorder::RecordedCommand;
+
+/// Which export path the pipeline took
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportRoute {
+    SmartRender,    // Stream copy with smart cuts
+    SinglePass,     // One filter_complex over the sources
+    Singletrack,    // Trim, concatenate, encode
+    Multitrack,     // Trim, concatenate the base track, composite overlays
+    AudioOnly,      // The audio mix alone, no video
+    ImageSequence,  // Numbered images (and the mix as WAV)
+}
+
+/// Everything a dry run found out about an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderPlan {
+    pub route: ExportRoute,
+    pub commands: Vec<RecordedCommand>,     // In the order the export runs them
+    pub intermediates: Vec<String>,         // Files written by one command and read by later ones
+    pub output_path: String,
+    pub expected_duration: f64,             // Length of the output (seconds)
+    pub script_path: Option<String>,        // Where the shell script was written, if requested
+}
+
+impl RenderPlan {
+    pub fn new(route: ExportRoute, commands: Vec<RecordedCommand>, output_path: String, expected_duration: f64) -> Self {
+        let intermediates = commands.iter()
+            .filter_map(|command| command.output())
+            .filter(|output| *output != output_path)
+            .map(|output| output.to_string())
+            .collect();
+        Self { route, commands, intermediates, output_path, expected_duration, script_path: None }
+    }
+
+    /// The plan as a POSIX shell script
+    ///
+    /// Paths under work_root (the job workspaces) are rewritten to $WORK, a fresh temporary
+    /// directory unless set by the caller. Concat lists are written before the commands that
+    /// read them. Source and output paths stay absolute.
+    pub fn shell_script(&self, work_root: &str) -> String {
+        let mut script = vec![
+            "#!/bin/sh".to_string(),
+            format!(
+                "# ClipForge render plan ({:?}): {} FFmpeg commands, expected duration {:.3}s",
+                self.route, self.commands.len(), self.expected_duration
+            ),
+            format!("# Output: {}", self.output_path),
+            "#".to_string(),
+            "# Intermediates go to $WORK (a new temporary directory unless set). Source paths are".to_string(),
+            "# absolute; edit them to render on another machine. Set FFMPEG to use another binary.".to_string(),
+            "set -e".to_string(),
+            "FFMPEG=\"${FFMPEG:-ffmpeg}\"".to_string(),
+            "WORK=\"${WORK:-$(mktemp -d)}\"".to_string(),
+            String::new(),
+        ];
+
+        // Workspace directories used by any command
+        let mut directories: Vec<&str> = Vec::new();
+        for command in &self.commands {
+            let paths = command.input_files.iter()
+                .map(|file| file.path.as_str())
+                .chain(command.output());
+            for path in paths.filter(|path| path.starts_with(work_root)) {
+                if let Some((directory, _)) = path.rsplit_once('/') {
+                    if directory.len() > work_root.len() && !directories.contains(&directory) {
+                        directories.push(directory);
+                    }
+                }
+            }
+        }
+        for directory in &directories {
+            script.push(format!("mkdir -p {}", shell_word(directory, work_root)));
+        }
+
+        for (index, command) in self.commands.iter().enumerate() {
+            script.push(String::new());
+            script.push(format!("# {}/{}", index + 1, self.commands.len()));
+            for file in &command.input_files {
+                let lines: Vec<String> = file.contents.lines().map(|line| shell_word(line, work_root)).collect();
+                script.push(format!("printf '%s\\n' {} > {}", lines.join(" "), shell_word(&file.path, work_root)));
+            }
+            let args: Vec<String> = command.args.iter().map(|arg| format!("  {}", shell_word(arg, work_root))).collect();
+            script.push(format!("\"$FFMPEG\" \\\n{}", args.join(" \\\n")));
+        }
+
+        script.push(String::new());
+        script.join("\n")
+    }
+}
+
+/// Quote a word for sh, with work_root replaced by "$WORK"
+///
+/// Example: "/tmp/clipforge_jobs/export_1/it's.mp4" -> "$WORK"'/export_1/it'\''s.mp4'
+fn shell_word(word: &str, work_root: &str) -> String {
+    let quoted = word.split(work_root)
+        .map(|part| {
+            if part.is_empty() {
+                String::new()
+            } else {
+                format!("'{}'", part.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\"$WORK\"");
+    if quoted.is_empty() { "''".to_string() } else { quoted }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffmpeg::recorder::InputFile;
+
+    fn command(args: &[&str], input_files: Vec<InputFile>) -> RecordedCommand {
+        RecordedCommand { input_files, ..RecordedCommand::new(args) }
+    }
+
+    #[test]
+    fn test_shell_words() {
+        let root = "/tmp/clipforge_jobs";
+        assert_eq!(shell_word("/tmp/clipforge_jobs/export_1/it's.mp4", root), "\"$WORK\"'/export_1/it'\\''s.mp4'");
+        assert_eq!(shell_word("[0:v]null[vout]", root), "'[0:v]null[vout]'");
+        assert_eq!(shell_word("", root), "''");
+    }
+
+    #[test]
+    fn test_plan_intermediates_and_script() {
+        let root = "/tmp/clipforge_jobs";
+        let plan = RenderPlan::new(
+            ExportRoute::Singletrack,
+            vec![
+                command(&["-ss", "1", "-i", "/media/a.mp4", "-t", "2", "-y", "/tmp/clipforge_jobs/export_1/clip_0_trimmed.mp4"], vec![]),
+                command(
+                    &["-f", "concat", "-safe", "0", "-i", "/tmp/clipforge_jobs/export_1/concat_list.txt", "-y", "/out/final.mp4"],
+                    vec![InputFile {
+                        path: "/tmp/clipforge_jobs/export_1/concat_list.txt".to_string(),
+                        contents: "file '/tmp/clipforge_jobs/export_1/clip_0_trimmed.mp4'".to_string(),
+                    }],
+                ),
+            ],
+            "/out/final.mp4".to_string(),
+            2.0,
+        );
+        assert_eq!(plan.intermediates, vec!["/tmp/clipforge_jobs/export_1/clip_0_trimmed.mp4"]);
+
+        let script = plan.shell_script(root);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert_eq!(script.matches("mkdir -p \"$WORK\"'/export_1'").count(), 1);
+        assert!(script.contains(
+            "printf '%s\\n' 'file '\\'''\"$WORK\"'/export_1/clip_0_trimmed.mp4'\\''' > \"$WORK\"'/export_1/concat_list.txt'"
+        ));
+        assert!(script.contains("\"$FFMPEG\" \\\n  '-ss' \\\n  '1' \\\n"));
+        assert!(!script.contains(root), "Workspace paths should all go through $WORK");
+    }
+}
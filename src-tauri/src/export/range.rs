@@ -0,0 +1,190 @@
+// Export range: render only a window of the timeline
+//
+// Everything timed on the timeline is cut to the window and re-based so the window
+// starts at 0: clips get new in/out points and positions, overlays and redactions new
+// start times. The rest of the pipeline then exports the result like a whole timeline.
+use serde::{Deserialize, Serialize};
+
+use super::pipeline::{ClipData, RedactionData, TextOverlayData, TransitionData, ZoomRegionData};
+
+/// Window of the timeline to export (seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExportRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl ExportRange {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.start.is_finite() || !self.end.is_finite() || self.start < 0.0 || self.end <= self.start {
+            return Err(format!("Invalid export range {:.3}s-{:.3}s", self.start, self.end));
+        }
+        Ok(())
+    }
+
+    /// Part of [start, start + duration) inside the range, as (start relative to the range, duration)
+    pub fn overlap(&self, start: f64, duration: f64) -> Option<(f64, f64)> {
+        let from = start.max(self.start);
+        let to = (start + duration).min(self.end);
+        if to - from > 0.0 {
+            Some((from - self.start, to - from))
+        } else {
+            None
+        }
+    }
+
+    /// Clips inside the range, trimmed to it and re-based
+    ///
+    /// Fades and J/L-cut audio at an edge that the range cuts off are dropped, as that
+    /// edge is no longer the clip's edge; zoom regions move with the new in point.
+    ///
+    /// Example: range 10-20, clip at 5s with in 0 / out 10 -> clip at 0s with in 5 / out 10
+    pub fn clips(&self, clips: &[ClipData]) -> Vec<ClipData> {
+        clips.iter()
+            .filter_map(|clip| {
+                let (start_time, duration) = self.overlap(clip.start_time, clip.out_point - clip.in_point)?;
+                let head = (self.start - clip.start_time).max(0.0);
+                let head_cut = head > 0.0;
+                let tail_cut = clip.start_time + (clip.out_point - clip.in_point) > self.end;
+
+                let zoom_regions = clip.zoom_regions.iter()
+                    .filter_map(|region| zoom_region_in(region, head, duration))
+                    .collect();
+
+                // Audio that leads or lags the video can't reach outside the range either
+                let space_after = (self.end - self.start) - (start_time + duration);
+                Some(ClipData {
+                    in_point: clip.in_point + head,
+                    out_point: clip.in_point + head + duration,
+                    start_time,
+                    fade_in_duration: if head_cut { 0.0 } else { clip.fade_in_duration.min(duration) },
+                    fade_out_duration: if tail_cut { 0.0 } else { clip.fade_out_duration.min(duration) },
+                    audio_lead: if head_cut { 0.0 } else { clip.audio_lead.min(start_time) },
+                    audio_lag: if tail_cut { 0.0 } else { clip.audio_lag.min(space_after) },
+                    zoom_regions,
+                    ..clip.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Transitions whose clips both remain in the range (clipped with ExportRange::clips),
+    /// shortened to fit clips the range cuts
+    pub fn transitions(&self, transitions: &[TransitionData], clips: &[ClipData]) -> Vec<TransitionData> {
+        let length = |id: u32| clips.iter()
+            .find(|clip| clip.id == id)
+            .map(|clip| clip.out_point - clip.in_point);
+        transitions.iter()
+            .filter_map(|t| {
+                let room = length(t.clip_id_before)?.min(length(t.clip_id_after)?);
+                Some(TransitionData { duration: t.duration.min(room), ..t.clone() })
+            })
+            .collect()
+    }
+
+    /// Text overlays visible in the range, cut at its edges and re-based
+    pub fn text_overlays(&self, overlays: &[TextOverlayData]) -> Vec<TextOverlayData> {
+        overlays.iter()
+            .filter_map(|overlay| {
+                let (start_time, duration) = self.overlap(overlay.start_time, overlay.duration)?;
+                Some(TextOverlayData { start_time, duration, ..overlay.clone() })
+            })
+            .collect()
+    }
+
+    /// Redactions active in the range, cut at its edges and re-based
+    pub fn redactions(&self, redactions: &[RedactionData]) -> Vec<RedactionData> {
+        redactions.iter()
+            .filter_map(|redaction| {
+                let (start_time, duration) = self.overlap(redaction.start_time, redaction.duration)?;
+                Some(RedactionData { start_time, duration, ..redaction.clone() })
+            })
+            .collect()
+    }
+}
+
+/// A zoom region (relative to the clip's first frame) after cutting head seconds off the
+/// clip and keeping duration seconds; easing at a cut edge is dropped
+fn zoom_region_in(region: &ZoomRegionData, head: f64, duration: f64) -> Option<ZoomRegionData> {
+    let window = ExportRange { start: head, end: head + duration };
+    let (start_time, kept) = window.overlap(region.start_time, region.duration)?;
+    let ease_in = if region.start_time < head { 0.0 } else { region.ease_in.min(kept) };
+    let ease_out = if region.start_time + region.duration > head + duration { 0.0 } else { region.ease_out };
+    Some(ZoomRegionData {
+        start_time,
+        duration: kept,
+        ease_in,
+        ease_out: ease_out.min(kept - ease_in),
+        ..region.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clips_are_trimmed_and_rebased() {
+        let range = ExportRange { start: 10.0, end: 20.0 };
+        let clip = |id: u32, start_time: f64, in_point: f64, out_point: f64| ClipData {
+            fade_in_duration: 1.0,
+            fade_out_duration: 1.0,
+            ..ClipData::fixture(id, start_time, in_point, out_point)
+        };
+        let clips = vec![
+            clip(1, 0.0, 0.0, 4.0),     // Ends before the range
+            clip(2, 5.0, 0.0, 10.0),    // Head cut: 5-15 -> 0-5, in point 5
+            clip(3, 15.0, 2.0, 5.0),    // Inside: 15-18 -> 5-8
+            clip(4, 19.0, 0.0, 10.0),   // Tail cut: 19-29 -> 9-10
+        ];
+
+        let kept = range.clips(&clips);
+        let spans: Vec<(u32, f64, f64, f64)> = kept.iter().map(|c| (c.id, c.start_time, c.in_point, c.out_point)).collect();
+        assert_eq!(spans, vec![(2, 0.0, 5.0, 10.0), (3, 5.0, 2.0, 5.0), (4, 9.0, 0.0, 1.0)]);
+
+        // Fades only survive at edges the range didn't cut
+        let fades: Vec<(f64, f64)> = kept.iter().map(|c| (c.fade_in_duration, c.fade_out_duration)).collect();
+        assert_eq!(fades, vec![(0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]);
+
+        let transitions = vec![TransitionData {
+            id: 1,
+            clip_id_before: 1,
+            clip_id_after: 2,
+            transition_type: "fade".to_string(),
+            duration: 0.5,
+            options: Default::default(),
+            audio_offset: 0.0,
+        }];
+        assert!(range.transitions(&transitions, &kept).is_empty(), "Clip 1 is outside the range");
+
+        // Only 1s of clip 4 is left, so a 2s transition into it is shortened to fit
+        let into_cut = TransitionData { id: 2, clip_id_before: 3, clip_id_after: 4, duration: 2.0, ..transitions[0].clone() };
+        assert_eq!(range.transitions(&[into_cut], &kept)[0].duration, 1.0);
+    }
+
+    #[test]
+    fn test_zoom_regions_follow_the_in_point() {
+        let region = ZoomRegionData {
+            start_time: 2.0,
+            duration: 4.0,
+            x: 0.0,
+            y: 0.0,
+            width: 50.0,
+            height: 50.0,
+            ease_in: 1.0,
+            ease_out: 1.0,
+        };
+
+        // 3s cut off the head: the region is already zoomed in at the new first frame
+        let cut = zoom_region_in(&region, 3.0, 10.0).unwrap();
+        assert_eq!((cut.start_time, cut.duration, cut.ease_in, cut.ease_out), (0.0, 3.0, 0.0, 1.0));
+        assert!(zoom_region_in(&region, 6.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(ExportRange { start: 12.0, end: 15.0 }.validate().is_ok());
+        assert!(ExportRange { start: 15.0, end: 12.0 }.validate().is_err());
+        assert!(ExportRange { start: -1.0, end: 12.0 }.validate().is_err());
+    }
+}
@@ -0,0 +1,260 @@
+// Image sequence export: one numbered image per frame, plus the mix as a WAV
+//
+// Frames come from the single-pass graph (see graph.rs), written by FFmpeg's image2 muxer
+// into the directory given as the export's output path. With alpha, the graph keeps the
+// background transparent, so compositing tools get the timeline's picture on its own.
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::capabilities::Feature;
+use super::encoder::ExportSettings;
+use super::graph;
+use super::pipeline::{ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData};
+use super::tracks::TrackData;
+
+/// Image formats of a sequence
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SequenceFormat {
+    Png,
+    Tiff,
+    Jpeg,
+}
+
+impl SequenceFormat {
+    fn encoder(&self) -> &'static str {
+        match self {
+            SequenceFormat::Png => "png",
+            SequenceFormat::Tiff => "tiff",
+            SequenceFormat::Jpeg => "mjpeg",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            SequenceFormat::Png => "png",
+            SequenceFormat::Tiff => "tif",
+            SequenceFormat::Jpeg => "jpg",
+        }
+    }
+}
+
+/// Settings for exporting the timeline as an image sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSequenceSettings {
+    pub format: SequenceFormat,
+    #[serde(default)]
+    pub alpha: bool,                // Transparent background instead of black / the gap fill (PNG and TIFF)
+    #[serde(default = "default_pattern")]
+    pub pattern: String,            // File name without extension; %d or %0Nd is the frame number
+    #[serde(default = "default_start_number")]
+    pub start_number: u32,          // Number of the first frame
+    #[serde(default = "default_audio")]
+    pub audio: bool,                // Also write the mix as a WAV next to the frames
+}
+
+fn default_pattern() -> String {
+    "frame_%05d".to_string()
+}
+
+fn default_start_number() -> u32 {
+    1
+}
+
+fn default_audio() -> bool {
+    true
+}
+
+impl ImageSequenceSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.alpha && self.format == SequenceFormat::Jpeg {
+            return Err("JPEG has no alpha channel; use PNG or TIFF for transparent sequences".to_string());
+        }
+        if self.pattern.contains('/') || self.pattern.contains('\\') {
+            return Err(format!("Frame pattern must be a file name, got \"{}\"", self.pattern));
+        }
+        if frame_number_directives(&self.pattern)? != 1 {
+            return Err(format!("Frame pattern needs exactly one frame number (%d or %05d), got \"{}\"", self.pattern));
+        }
+        Ok(())
+    }
+
+    /// Encoder arguments for the frames
+    ///
+    /// Example (PNG with alpha): ["-c:v", "png", "-pix_fmt", "rgba"]
+    fn encoder_args(&self) -> Vec<&'static str> {
+        let pix_fmt = if self.alpha { "rgba" } else { "rgb24" };
+        match self.format {
+            SequenceFormat::Png => vec!["-c:v", "png", "-pix_fmt", pix_fmt],
+            // LZW is read by every compositing tool and much smaller than raw
+            SequenceFormat::Tiff => vec!["-c:v", "tiff", "-pix_fmt", pix_fmt, "-compression_algo", "lzw"],
+            SequenceFormat::Jpeg => vec!["-c:v", "mjpeg", "-pix_fmt", "yuvj420p", "-q:v", "2"],
+        }
+    }
+
+    /// File name of the WAV: the pattern without its frame number ("shot_%04d" -> "shot.wav")
+    fn audio_file_name(&self) -> String {
+        let mut name = String::new();
+        let mut chars = self.pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                name.push(c);
+            } else if chars.peek() == Some(&'%') {
+                chars.next();
+                name.push('%');
+            } else {
+                while chars.next().is_some_and(|d| d != 'd') {}
+            }
+        }
+        let name = name.trim_matches(|c: char| c == '_' || c == '-' || c == '.' || c == ' ');
+        format!("{}.wav", if name.is_empty() { "audio" } else { name })
+    }
+}
+
+/// Number of %d / %0Nd directives in an image2 pattern ("%%" is a literal percent sign)
+fn frame_number_directives(pattern: &str) -> Result<usize, String> {
+    let mut count = 0;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some('%') => {}
+            Some(first) => {
+                let mut next = Some(first);
+                while next.is_some_and(|d| d.is_ascii_digit()) {
+                    next = chars.next();
+                }
+                if next != Some('d') {
+                    return Err(format!("Frame pattern may only use %d or %0Nd, got \"{}\"", pattern));
+                }
+                count += 1;
+            }
+            None => return Err(format!("Frame pattern ends with a lone %: \"{}\"", pattern)),
+        }
+    }
+    Ok(count)
+}
+
+impl ExportPipeline {
+    /// Render the timeline as numbered images into the directory settings.output_path
+    ///
+    /// clips are prepared as in export_timeline.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn export_image_sequence(
+        &self,
+        clips: &[ClipData],
+        transitions: &[TransitionData],
+        text_overlays: &[TextOverlayData],
+        redactions: &[RedactionData],
+        tracks: &[TrackData],
+        settings: &ExportSettings,
+        sequence: &ImageSequenceSettings,
+    ) -> Result<String, String> {
+        sequence.validate()?;
+        if sequence.alpha && settings.gap_fill.slate_path.is_some() {
+            return Err("Transparent sequences can't use a gap slate".to_string());
+        }
+        if sequence.alpha && settings.layout.is_tiled() {
+            return Err(format!("Transparent sequences need the picture-in-picture layout, not {:?}", settings.layout));
+        }
+        eprintln!("🖼️  Exporting {:?} sequence {} (alpha: {}) to {}", sequence.format, sequence.pattern, sequence.alpha, settings.output_path);
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        let mut required = vec![
+            (Feature::Encoder(sequence.format.encoder()), "this image format"),
+            (Feature::Muxer("image2"), "image sequences"),
+        ];
+        if sequence.audio {
+            required.push((Feature::Encoder("pcm_s16le"), "the sequence audio"));
+        }
+        ffmpeg.require(&required)?;
+
+        let directory = Path::new(&settings.output_path);
+        if !ffmpeg.is_recording() {
+            std::fs::create_dir_all(directory)
+                .map_err(|e| format!("Failed to create sequence directory: {}", e))?;
+        }
+
+        ffmpeg.set_progress(5.0, "Probing sources...".to_string(), None);
+        let sources = graph::probe_sources(&ffmpeg, clips)?;
+        let graph = self.build_single_pass_graph(clips, transitions, text_overlays, redactions, tracks, settings, &sources)?;
+        eprintln!("🖼️  SEQUENCE: {} inputs, filter graph: {}", graph.inputs.len(), graph.filter_complex);
+
+        // Frames only; the mix goes to its own file below
+        let frames_path = directory.join(format!("{}.{}", sequence.pattern, sequence.format.extension()));
+        let mut args: Vec<String> = graph.inputs.iter().flatten().cloned().collect();
+        args.push("-filter_complex".to_string());
+        args.push(format!("{};{}anullsink", graph.filter_complex, graph.audio_label));
+        args.push("-map".to_string());
+        args.push(graph.video_label.clone());
+        args.extend(sequence.encoder_args().into_iter().map(|s| s.to_string()));
+        args.push("-t".to_string());
+        args.push(format!("{:.3}", graph.duration));
+        args.push("-start_number".to_string());
+        args.push(sequence.start_number.to_string());
+        args.extend(["-f", "image2", "-y"].iter().map(|s| s.to_string()));
+        args.push(frames_path.display().to_string());
+
+        ffmpeg.set_progress(10.0, "Writing frames...".to_string(), None);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+
+        if sequence.audio && clips.iter().any(|clip| clip.audio_enabled) {
+            ffmpeg.set_progress(90.0, "Writing audio...".to_string(), None);
+            let mut args = self.audio_mix_args(clips, transitions, tracks, settings, &sources)?;
+            args.extend(["-c:a", "pcm_s16le", "-ar", "48000", "-ac", "2", "-y"].iter().map(|s| s.to_string()));
+            args.push(directory.join(sequence.audio_file_name()).display().to_string());
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+        }
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(settings.output_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(format: SequenceFormat, pattern: &str) -> ImageSequenceSettings {
+        ImageSequenceSettings {
+            format,
+            alpha: false,
+            pattern: pattern.to_string(),
+            start_number: 1,
+            audio: true,
+        }
+    }
+
+    #[test]
+    fn test_frame_patterns() {
+        assert!(sequence(SequenceFormat::Png, "frame_%05d").validate().is_ok());
+        assert!(sequence(SequenceFormat::Png, "100%%_%d").validate().is_ok());
+        assert!(sequence(SequenceFormat::Png, "frame").validate().is_err(), "No frame number");
+        assert!(sequence(SequenceFormat::Png, "%d_%d").validate().is_err());
+        assert!(sequence(SequenceFormat::Png, "frame_%s").validate().is_err());
+        assert!(sequence(SequenceFormat::Png, "shots/%04d").validate().is_err());
+
+        assert_eq!(sequence(SequenceFormat::Tiff, "shot_%04d").audio_file_name(), "shot.wav");
+        assert_eq!(sequence(SequenceFormat::Tiff, "%05d").audio_file_name(), "audio.wav");
+    }
+
+    #[test]
+    fn test_alpha_formats() {
+        let mut png = sequence(SequenceFormat::Png, "frame_%05d");
+        png.alpha = true;
+        assert_eq!(png.encoder_args(), vec!["-c:v", "png", "-pix_fmt", "rgba"]);
+
+        let mut jpeg = sequence(SequenceFormat::Jpeg, "frame_%05d");
+        jpeg.alpha = true;
+        assert!(jpeg.validate().is_err());
+    }
+}
@@ -0,0 +1,296 @@
+// Smart rendering: stream-copy clips that need no re-encode
+//
+// A clip can be copied when it has no effects and its source matches the other clips'
+// codec parameters. Copying has to start on a keyframe, so the frames between the in
+// point and the next keyframe are re-encoded (smart cut) and the rest is copied.
+//
+// Re-encoded heads get the source's profile, level, pixel format and audio layout, and
+// every segment is written as Annex B MPEG-TS. That keeps each segment's SPS/PPS in-band
+// in front of its keyframes, so the joined output switches parameter sets where the
+// segments change instead of relying on the first segment's out-of-band copy.
+use crate::ffmpeg::metadata::VideoMetadata;
+
+use super::pipeline::ClipData;
+use super::tracks::{self, TrackData};
+
+/// Video codecs that can be stream-copied, with the encoder used for smart-cut heads
+const COPY_VIDEO_CODECS: &[(&str, &str)] = &[("h264", "libx264"), ("hevc", "libx265")];
+
+/// Audio codecs that can be stream-copied
+const COPY_AUDIO_CODECS: &[&str] = &["aac"];
+
+/// Output containers the copied streams are muxed into
+const COPY_CONTAINERS: &[&str] = &["mp4", "mov", "m4v", "mkv"];
+
+/// Cut points closer than this (seconds) to a keyframe are treated as on it
+const KEYFRAME_TOLERANCE: f64 = 0.001;
+
+/// How a planned segment is produced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentMode {
+    Copy,       // Stream copy from a keyframe
+    Encode,     // Re-encode with parameters matching the source
+}
+
+/// A piece of a clip in the smart render plan (times are in the source)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedSegment {
+    pub clip_index: usize,
+    pub start: f64,
+    pub end: f64,
+    pub mode: SegmentMode,
+}
+
+/// Stream-copy render of a single-track timeline
+#[derive(Debug, Clone)]
+pub struct SmartRenderPlan {
+    pub clips: Vec<ClipData>,               // Clips in timeline order
+    pub segments: Vec<PlannedSegment>,      // Segments in output order
+    pub source: VideoMetadata,              // Stream parameters shared by every clip
+    pub encode_args: Vec<String>,           // Encoder arguments for re-encoded segments
+}
+
+impl SmartRenderPlan {
+    /// Bitstream filter that converts copied video to Annex B for MPEG-TS segments
+    pub fn annexb_filter(&self) -> &'static str {
+        if self.source.codec == "hevc" {
+            "hevc_mp4toannexb"
+        } else {
+            "h264_mp4toannexb"
+        }
+    }
+}
+
+/// Why a clip must go through the normal render path, if it must
+pub fn clip_effects(clip: &ClipData, tracks: &[TrackData]) -> Option<String> {
+    if !clip.video_enabled || !clip.audio_enabled {
+        return Some(format!("clip {} is unlinked (video or audio disabled)", clip.id));
+    }
+    if clip.is_muted || clip.volume != 100 || clip.fade_in_duration > 0.0 || clip.fade_out_duration > 0.0 {
+        return Some(format!("clip {} has audio adjustments", clip.id));
+    }
+    if !clip.zoom_regions.is_empty() {
+        return Some(format!("clip {} has zoom regions", clip.id));
+    }
+    if clip.audio_lead != 0.0 || clip.audio_lag != 0.0 {
+        return Some(format!("clip {} has a J/L cut", clip.id));
+    }
+    if !tracks::audio_filters(tracks, clip.track).is_empty() {
+        return Some(format!("track {} has gain or pan", clip.track));
+    }
+    None
+}
+
+/// Whether the output path's container can take stream-copied H.264/HEVC and AAC
+pub fn container_supported(output_path: &str) -> bool {
+    std::path::Path::new(output_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| COPY_CONTAINERS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Encoder arguments for smart-cut heads that match a source's stream parameters
+///
+/// The video encoder gets the source's profile, level and pixel format, and the audio
+/// encoder its sample rate and channel count, so re-encoded segments can be joined with
+/// copied ones. Fails when the codecs can't be copied or the profile has no encoder match.
+///
+/// Example: h264 High 4.0 yuv420p -> ["-c:v", "libx264", "-profile:v", "high", "-level:v", "4.0", ...]
+pub fn head_encoder_args(metadata: &VideoMetadata) -> Result<Vec<String>, String> {
+    let encoder = COPY_VIDEO_CODECS.iter()
+        .find(|(codec, _)| *codec == metadata.codec)
+        .map(|(_, encoder)| *encoder)
+        .ok_or_else(|| format!("video codec {} can't be stream-copied", metadata.codec))?;
+
+    match &metadata.audio_codec {
+        Some(codec) if COPY_AUDIO_CODECS.contains(&codec.as_str()) => {}
+        Some(codec) => return Err(format!("audio codec {} can't be stream-copied", codec)),
+        None => return Err("source has no audio stream".to_string()),
+    }
+
+    let mut args: Vec<String> = vec!["-c:v".to_string(), encoder.to_string()];
+    if let Some(profile) = &metadata.profile {
+        args.push("-profile:v".to_string());
+        args.push(encoder_profile(&metadata.codec, profile)?.to_string());
+    }
+    if let Some(level) = metadata.level {
+        // ffprobe reports H.264 levels times 10 and HEVC levels times 30
+        if metadata.codec == "hevc" {
+            args.push("-x265-params".to_string());
+            args.push(format!("level-idc={:.1}", level as f64 / 30.0));
+        } else {
+            args.push("-level:v".to_string());
+            args.push(format!("{}.{}", level / 10, level % 10));
+        }
+    }
+    if let Some(pix_fmt) = &metadata.pix_fmt {
+        args.push("-pix_fmt".to_string());
+        args.push(pix_fmt.clone());
+    }
+    args.extend(["-crf", "18", "-c:a", "aac", "-b:a", "192k"].iter().map(|s| s.to_string()));
+    if let Some(sample_rate) = metadata.audio_sample_rate {
+        args.push("-ar".to_string());
+        args.push(sample_rate.to_string());
+    }
+    if let Some(channels) = metadata.audio_channels {
+        args.push("-ac".to_string());
+        args.push(channels.to_string());
+    }
+    Ok(args)
+}
+
+/// Encoder profile name for a profile as ffprobe reports it
+fn encoder_profile(codec: &str, profile: &str) -> Result<&'static str, String> {
+    let encoder_profile = match (codec, profile) {
+        ("h264", "Baseline") | ("h264", "Constrained Baseline") => Some("baseline"),
+        ("h264", "Main") => Some("main"),
+        ("h264", "High") => Some("high"),
+        ("h264", "High 10") => Some("high10"),
+        ("h264", "High 4:2:2") => Some("high422"),
+        ("h264", "High 4:4:4 Predictive") => Some("high444"),
+        ("hevc", "Main") => Some("main"),
+        ("hevc", "Main 10") => Some("main10"),
+        _ => None,
+    };
+    encoder_profile.ok_or_else(|| format!("{} profile {} can't be matched for smart-cut heads", codec, profile))
+}
+
+/// Check that a source matches the first clip's stream parameters, so copied and
+/// re-encoded segments from both can be concatenated without re-encoding
+pub fn streams_compatible(first: &VideoMetadata, other: &VideoMetadata) -> Result<(), String> {
+    if first.codec != other.codec {
+        return Err(format!("video codecs differ ({} vs {})", first.codec, other.codec));
+    }
+    if first.width != other.width || first.height != other.height {
+        return Err(format!(
+            "frame sizes differ ({}x{} vs {}x{})",
+            first.width, first.height, other.width, other.height
+        ));
+    }
+    if (first.fps - other.fps).abs() > 0.01 {
+        return Err(format!("frame rates differ ({:.3} vs {:.3})", first.fps, other.fps));
+    }
+    if first.pix_fmt != other.pix_fmt {
+        return Err("pixel formats differ".to_string());
+    }
+    if first.profile != other.profile || first.level != other.level {
+        return Err("codec profiles or levels differ".to_string());
+    }
+    if first.audio_codec != other.audio_codec
+        || first.audio_sample_rate != other.audio_sample_rate
+        || first.audio_channels != other.audio_channels
+    {
+        return Err("audio parameters differ".to_string());
+    }
+    Ok(())
+}
+
+/// Split a clip into re-encoded and copied segments
+///
+/// keyframes are source timestamps (sorted). Copy starts at the first keyframe inside the
+/// clip; anything before it is re-encoded. If there is no keyframe inside the clip, the
+/// whole clip is re-encoded. The end of a copy needs no keyframe.
+///
+/// Example: in 1.5, out 60.0, keyframes [0.0, 2.0, 4.0] -> Encode 1.5-2.0, Copy 2.0-60.0
+pub fn plan_clip(clip_index: usize, in_point: f64, out_point: f64, keyframes: &[f64]) -> Vec<PlannedSegment> {
+    let first_keyframe = keyframes.iter()
+        .copied()
+        .find(|&k| k >= in_point - KEYFRAME_TOLERANCE && k < out_point - KEYFRAME_TOLERANCE);
+
+    let segment = |start: f64, end: f64, mode: SegmentMode| PlannedSegment { clip_index, start, end, mode };
+
+    match first_keyframe {
+        None => vec![segment(in_point, out_point, SegmentMode::Encode)],
+        Some(keyframe) if keyframe - in_point <= KEYFRAME_TOLERANCE => {
+            vec![segment(keyframe.max(in_point), out_point, SegmentMode::Copy)]
+        }
+        Some(keyframe) => vec![
+            segment(in_point, keyframe, SegmentMode::Encode),
+            segment(keyframe, out_point, SegmentMode::Copy),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(codec: &str, width: u32) -> VideoMetadata {
+        VideoMetadata {
+            duration: 60.0,
+            width,
+            height: 720,
+            format: "mov,mp4,m4a,3gp,3g2,mj2".to_string(),
+            codec: codec.to_string(),
+            fps: 30.0,
+            bitrate: None,
+            audio_codec: Some("aac".to_string()),
+            file_size: 0,
+            pix_fmt: Some("yuv420p".to_string()),
+            audio_sample_rate: Some(48000),
+            audio_channels: Some(2),
+            profile: Some(if codec == "hevc" { "Main" } else { "High" }.to_string()),
+            level: Some(if codec == "hevc" { 93 } else { 31 }),
+        }
+    }
+
+    #[test]
+    fn test_plan_clip_smart_cut() {
+        let keyframes = [0.0, 2.0, 4.0, 6.0];
+
+        // Starts on a keyframe: one copy segment
+        assert_eq!(
+            plan_clip(0, 2.0, 5.0, &keyframes),
+            vec![PlannedSegment { clip_index: 0, start: 2.0, end: 5.0, mode: SegmentMode::Copy }]
+        );
+
+        // Starts between keyframes: re-encode the head, copy from the next keyframe
+        assert_eq!(
+            plan_clip(1, 1.5, 5.0, &keyframes),
+            vec![
+                PlannedSegment { clip_index: 1, start: 1.5, end: 2.0, mode: SegmentMode::Encode },
+                PlannedSegment { clip_index: 1, start: 2.0, end: 5.0, mode: SegmentMode::Copy },
+            ]
+        );
+
+        // No keyframe inside the clip: re-encode everything
+        assert_eq!(
+            plan_clip(2, 4.5, 5.5, &keyframes),
+            vec![PlannedSegment { clip_index: 2, start: 4.5, end: 5.5, mode: SegmentMode::Encode }]
+        );
+    }
+
+    #[test]
+    fn test_head_encoder_matches_source() {
+        let args = head_encoder_args(&metadata("h264", 1280)).unwrap();
+        let arg = |name: &str| args.iter().position(|a| a == name).map(|i| args[i + 1].as_str());
+        assert_eq!(arg("-c:v"), Some("libx264"));
+        assert_eq!(arg("-profile:v"), Some("high"));
+        assert_eq!(arg("-level:v"), Some("3.1"));
+        assert_eq!(arg("-pix_fmt"), Some("yuv420p"));
+        assert_eq!(arg("-ar"), Some("48000"));
+
+        let hevc = VideoMetadata { profile: Some("Main 10".to_string()), level: Some(93), ..metadata("hevc", 1280) };
+        let args = head_encoder_args(&hevc).unwrap();
+        assert!(args.windows(2).any(|w| w == ["-profile:v", "main10"]));
+        assert!(args.windows(2).any(|w| w == ["-x265-params", "level-idc=3.1"]));
+
+        // A profile the encoder can't produce can't be smart-cut
+        let extended = VideoMetadata { profile: Some("Extended".to_string()), ..metadata("h264", 1280) };
+        assert!(head_encoder_args(&extended).is_err());
+    }
+
+    #[test]
+    fn test_stream_compatibility() {
+        assert!(streams_compatible(&metadata("h264", 1280), &metadata("h264", 1280)).is_ok());
+        assert!(streams_compatible(&metadata("h264", 1280), &metadata("h264", 1920)).is_err());
+        assert!(streams_compatible(&metadata("h264", 1280), &metadata("hevc", 1280)).is_err());
+
+        assert!(head_encoder_args(&metadata("hevc", 1280)).is_ok());
+        assert!(head_encoder_args(&metadata("vp9", 1280)).is_err());
+
+        assert!(container_supported("/tmp/out.MP4"));
+        assert!(!container_supported("/tmp/out.webm"));
+    }
+}
@@ -0,0 +1,306 @@
+// Audio stems: the timeline mix split by track or by track tag
+//
+// Each stem is the timeline audio with the clips of every other stem switched off, built
+// by the same graph as audio-only exports (see audio.rs). Stems are taken before the master
+// bus, so together they add up to the unmastered mix. They are written as WAV files next
+// to the main output, muxed into it as extra audio streams (MKV/MOV), or both.
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::workspace::JobWorkspace;
+use crate::ffmpeg::FFmpegWrapper;
+use super::encoder::ExportSettings;
+use super::graph;
+use super::master::MasterBus;
+use super::pipeline::{ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData};
+use super::plan::ExportRoute;
+use super::tracks::TrackData;
+
+/// How tracks are grouped into stems
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StemGrouping {
+    #[default]
+    Track,      // One stem per track
+    Tag,        // One stem per track tag; untagged tracks get a stem of their own
+}
+
+/// Settings for exporting stems alongside the main output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StemSettings {
+    #[serde(default)]
+    pub group_by: StemGrouping,
+    #[serde(default = "default_wav_files")]
+    pub wav_files: bool,                // Write "<output name>_<stem>.wav" next to the output
+    #[serde(default)]
+    pub embed: bool,                    // Add the stems as extra audio streams of the output (MKV/MOV only)
+    #[serde(default)]
+    pub language: Option<String>,       // ISO 639-2 code tagged on every audio stream (e.g. "eng")
+}
+
+fn default_wav_files() -> bool {
+    true
+}
+
+impl StemSettings {
+    pub fn validate(&self, output_path: &str) -> Result<(), String> {
+        if !self.wav_files && !self.embed {
+            return Err("Stems must be written as WAV files, embedded, or both".to_string());
+        }
+        if self.embed && !matches!(extension(output_path).as_deref(), Some("mkv") | Some("mov")) {
+            return Err(format!("Stems can only be embedded in MKV or MOV files, not {}", output_path));
+        }
+        if let Some(language) = &self.language {
+            if language.len() != 3 || !language.chars().all(|c| c.is_ascii_lowercase()) {
+                return Err(format!("Stem language must be an ISO 639-2 code like \"eng\", got \"{}\"", language));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One stem: its name and the tracks mixed into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stem {
+    pub name: String,
+    pub tracks: Vec<u32>,
+}
+
+/// Stems of a prepared timeline in track order; only tracks with audible clips get one
+///
+/// Example (by tag): tracks 0 "Voice", 1 "music", 2 untagged, 3 "voice" -> voice [0, 3], music [1], track_2 [2]
+pub fn plan_stems(clips: &[ClipData], tracks: &[TrackData], grouping: StemGrouping) -> Vec<Stem> {
+    let mut audible: Vec<u32> = clips.iter()
+        .filter(|c| c.audio_enabled)
+        .map(|c| c.track)
+        .collect();
+    audible.sort();
+    audible.dedup();
+
+    let mut stems: Vec<Stem> = Vec::new();
+    for track in audible {
+        let tag = match grouping {
+            StemGrouping::Tag => tracks.iter()
+                .find(|t| t.index == track)
+                .and_then(|t| t.tag.as_deref())
+                .map(stem_name)
+                .filter(|name| !name.is_empty()),
+            StemGrouping::Track => None,
+        };
+        let name = tag.unwrap_or_else(|| format!("track_{}", track));
+        match stems.iter_mut().find(|stem| stem.name == name) {
+            Some(stem) => stem.tracks.push(track),
+            None => stems.push(Stem { name, tracks: vec![track] }),
+        }
+    }
+    stems
+}
+
+/// A tag as a file name part: lowercase, with anything but letters, digits, - and _ replaced
+fn stem_name(tag: &str) -> String {
+    tag.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+}
+
+/// WAV file of a stem next to the output: /out/talk.mp4 + "voice" -> /out/talk_voice.wav
+fn stem_path(output_path: &str, name: &str) -> PathBuf {
+    let output = Path::new(output_path);
+    let base = output.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "export".to_string());
+    output.with_file_name(format!("{}_{}.wav", base, name))
+}
+
+impl ExportPipeline {
+    /// Render the timeline to settings.output_path, then its stems
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn render_with_stems(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: ExportSettings,
+        stems: &StemSettings,
+    ) -> Result<(ExportRoute, f64), String> {
+        stems.validate(&settings.output_path)?;
+        if stems.embed && settings.audio_only.is_some() {
+            return Err("Stems can't be embedded in audio-only exports".to_string());
+        }
+
+        // Stems are planned on the timeline the main render sees (range cut, track settings applied)
+        let (stem_clips, stem_transitions, _, _) = self.prepare_timeline(
+            clips.clone(), transitions.clone(), vec![], vec![], &tracks, settings.range
+        )?;
+        let plan = plan_stems(&stem_clips, &tracks, stems.group_by);
+        if plan.is_empty() {
+            return Err("No audible tracks to export stems from".to_string());
+        }
+        eprintln!("🎚️  Exporting {} stems: {:?}", plan.len(), plan.iter().map(|s| &s.name).collect::<Vec<_>>());
+
+        // Embedding muxes the stems into the main render, so that is rendered into the workspace
+        let workspace = JobWorkspace::create("stems")?;
+        let output_path = settings.output_path.clone();
+        let main_path = if stems.embed {
+            let extension = extension(&output_path).unwrap_or_default();
+            workspace.file(&format!("main.{}", extension)).display().to_string()
+        } else {
+            output_path.clone()
+        };
+        let main_settings = ExportSettings {
+            output_path: main_path.clone(),
+            stems: None,
+            ..settings.clone()
+        };
+        let rendered = self.render_timeline(clips, transitions, text_overlays, redactions, tracks.clone(), main_settings)?;
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+        let sources = graph::probe_sources(&ffmpeg, &stem_clips)?;
+        let stem_settings = ExportSettings {
+            master: MasterBus::default(),
+            ..settings
+        };
+
+        let mut stem_paths = Vec::with_capacity(plan.len());
+        for (index, stem) in plan.iter().enumerate() {
+            ffmpeg.set_progress(
+                90.0 + (index as f64 / plan.len() as f64) * 10.0,
+                format!("Rendering stem {} of {} ({})...", index + 1, plan.len(), stem.name),
+                None
+            );
+
+            let path = if stems.wav_files {
+                stem_path(&output_path, &stem.name)
+            } else {
+                workspace.file(&format!("stem_{}.wav", stem.name))
+            };
+            let clips: Vec<ClipData> = stem_clips.iter()
+                .map(|c| ClipData { audio_enabled: c.audio_enabled && stem.tracks.contains(&c.track), ..c.clone() })
+                .collect();
+
+            let mut args = self.audio_mix_args(&clips, &stem_transitions, &tracks, &stem_settings, &sources)?;
+            args.extend(["-c:a", "pcm_s16le", "-ar", "48000", "-ac", "2", "-y"].iter().map(|s| s.to_string()));
+            args.push(path.display().to_string());
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+            stem_paths.push(path);
+        }
+
+        if stems.embed {
+            ffmpeg.set_progress(99.0, "Embedding stems...".to_string(), None);
+            self.embed_stems(&ffmpeg, &main_path, &plan, &stem_paths, stems.language.as_deref(), &output_path)?;
+        }
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(rendered)
+    }
+
+    /// Mux stem WAVs into a copy of the main render as extra audio streams
+    ///
+    /// The main mix stays the first (default) audio stream, titled "Mix"; each stem follows,
+    /// titled with its name. Everything is stream-copied; MKV and MOV both carry PCM.
+    fn embed_stems(
+        &self,
+        ffmpeg: &FFmpegWrapper,
+        main_path: &str,
+        stems: &[Stem],
+        stem_paths: &[PathBuf],
+        language: Option<&str>,
+        output_path: &str,
+    ) -> Result<(), String> {
+        let mut args = vec!["-i".to_string(), main_path.to_string()];
+        for path in stem_paths {
+            args.push("-i".to_string());
+            args.push(path.display().to_string());
+        }
+        args.extend(["-map", "0:v", "-map", "0:a:0"].iter().map(|s| s.to_string()));
+        for index in 0..stem_paths.len() {
+            args.push("-map".to_string());
+            args.push(format!("{}:a", index + 1));
+        }
+        args.extend(["-c", "copy"].iter().map(|s| s.to_string()));
+
+        let titles = std::iter::once("Mix").chain(stems.iter().map(|s| s.name.as_str()));
+        for (stream, title) in titles.enumerate() {
+            args.push(format!("-metadata:s:a:{}", stream));
+            args.push(format!("title={}", title));
+            args.push(format!("-disposition:a:{}", stream));
+            args.push(if stream == 0 { "default" } else { "0" }.to_string());
+        }
+        if let Some(language) = language {
+            args.push("-metadata:s:a".to_string());
+            args.push(format!("language={}", language));
+        }
+        args.push("-y".to_string());
+        args.push(output_path.to_string());
+
+        eprintln!("🎚️  Embedding stems: {:?}", args);
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        ffmpeg.execute_command(&args_refs)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(index: u32, tag: Option<&str>) -> TrackData {
+        TrackData {
+            index,
+            muted: false,
+            solo: false,
+            hidden: false,
+            locked: false,
+            gain_db: 0.0,
+            pan: 0.0,
+            tag: tag.map(|t| t.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_stems_by_track_and_tag() {
+        let clip = |id: u32, track: u32| ClipData { track, ..ClipData::fixture(id, 0.0, 0.0, 1.0) };
+        let silent = ClipData { audio_enabled: false, ..clip(5, 4) };
+        let clips = vec![clip(1, 0), clip(2, 1), clip(3, 2), clip(4, 3), silent];
+        let tracks = vec![track(0, Some("Voice")), track(1, Some("music")), track(3, Some("voice"))];
+
+        let by_track = plan_stems(&clips, &tracks, StemGrouping::Track);
+        let names: Vec<&str> = by_track.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["track_0", "track_1", "track_2", "track_3"], "Track 4 has no audio");
+
+        let by_tag = plan_stems(&clips, &tracks, StemGrouping::Tag);
+        assert_eq!(by_tag, vec![
+            Stem { name: "voice".to_string(), tracks: vec![0, 3] },
+            Stem { name: "music".to_string(), tracks: vec![1] },
+            Stem { name: "track_2".to_string(), tracks: vec![2] },
+        ]);
+
+        assert_eq!(stem_path("/out/talk.mkv", "voice"), PathBuf::from("/out/talk_voice.wav"));
+        assert_eq!(stem_name(" SFX / Foley "), "sfx___foley");
+    }
+
+    #[test]
+    fn test_invalid_stem_settings() {
+        let stems: StemSettings = serde_json::from_str(r#"{"embed":true,"language":"eng"}"#).unwrap();
+        assert!(stems.validate("/out/talk.mkv").is_ok());
+        assert!(stems.validate("/out/talk.mp4").is_err(), "MP4 can't carry the PCM stems");
+        let stems = StemSettings { language: Some("english".to_string()), ..stems };
+        assert!(stems.validate("/out/talk.mov").is_err());
+    }
+}
@@ -0,0 +1,353 @@
+// Adaptive streaming output: an HLS and/or DASH bitrate ladder in a directory
+//
+// The timeline is rendered to an MP4 in a job workspace as for any export, each rendition
+// of the ladder is encoded from it with keyframes forced on segment boundaries, and the
+// renditions are stream-copied into the hls and dash muxers. Encoding once keeps HLS and
+// DASH segments identical in timing when both are written.
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffmpeg::capabilities::Feature;
+use crate::ffmpeg::workspace::JobWorkspace;
+use super::encoder::ExportSettings;
+use super::pipeline::{ClipData, ExportPipeline, RedactionData, TextOverlayData, TransitionData};
+use super::plan::ExportRoute;
+use super::tracks::TrackData;
+
+/// HLS master playlist, written at the top of the output directory
+const MASTER_PLAYLIST: &str = "master.m3u8";
+
+/// DASH manifest, written at the top of the output directory
+const DASH_MANIFEST: &str = "manifest.mpd";
+
+/// One rung of the ladder
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rendition {
+    pub height: u32,                    // Width follows the aspect ratio
+    pub video_bitrate_kbps: u32,
+    #[serde(default = "default_audio_bitrate")]
+    pub audio_bitrate_kbps: u32,
+}
+
+fn default_audio_bitrate() -> u32 {
+    128
+}
+
+impl Rendition {
+    fn name(&self) -> String {
+        format!("{}p", self.height)
+    }
+}
+
+/// Settings for exporting the timeline as an adaptive bitrate ladder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingSettings {
+    #[serde(default = "default_renditions")]
+    pub renditions: Vec<Rendition>,     // Highest first by convention; rungs taller than the export are skipped
+    #[serde(default = "default_hls")]
+    pub hls: bool,                      // master.m3u8 plus a playlist and .ts segments per rendition
+    #[serde(default)]
+    pub dash: bool,                     // manifest.mpd plus .m4s segments
+    #[serde(default = "default_segment_duration")]
+    pub segment_duration: f64,          // Target segment length (seconds)
+}
+
+fn default_renditions() -> Vec<Rendition> {
+    vec![
+        Rendition { height: 1080, video_bitrate_kbps: 5000, audio_bitrate_kbps: 128 },
+        Rendition { height: 720, video_bitrate_kbps: 2800, audio_bitrate_kbps: 128 },
+        Rendition { height: 480, video_bitrate_kbps: 1400, audio_bitrate_kbps: 96 },
+    ]
+}
+
+fn default_hls() -> bool {
+    true
+}
+
+fn default_segment_duration() -> f64 {
+    6.0
+}
+
+impl StreamingSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.hls && !self.dash {
+            return Err("Streaming exports need HLS, DASH, or both".to_string());
+        }
+        if !(self.segment_duration.is_finite() && (1.0..=60.0).contains(&self.segment_duration)) {
+            return Err(format!("Segment duration must be between 1 and 60 seconds, got {}", self.segment_duration));
+        }
+        if self.renditions.is_empty() {
+            return Err("Streaming exports need at least one rendition".to_string());
+        }
+        for (i, rendition) in self.renditions.iter().enumerate() {
+            if !(144..=2160).contains(&rendition.height) || rendition.height % 2 != 0 {
+                return Err(format!("Rendition height must be an even number between 144 and 2160, got {}", rendition.height));
+            }
+            if self.renditions[..i].iter().any(|r| r.height == rendition.height) {
+                return Err(format!("Rendition {} is listed more than once", rendition.name()));
+            }
+            if !(100..=50_000).contains(&rendition.video_bitrate_kbps) {
+                return Err(format!(
+                    "Rendition {} video bitrate must be between 100 and 50000 kbps, got {}",
+                    rendition.name(), rendition.video_bitrate_kbps
+                ));
+            }
+            if !(32..=512).contains(&rendition.audio_bitrate_kbps) {
+                return Err(format!(
+                    "Rendition {} audio bitrate must be between 32 and 512 kbps, got {}",
+                    rendition.name(), rendition.audio_bitrate_kbps
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encoders, filters and muxers the ladder uses
+    pub fn required_features(&self) -> Vec<(Feature, &'static str)> {
+        let mut required = vec![
+            (Feature::Filter("scale"), "the rendition sizes"),
+            (Feature::Encoder("libx264"), "the renditions"),
+            (Feature::Encoder("aac"), "the renditions"),
+        ];
+        if self.hls {
+            required.push((Feature::Muxer("hls"), "HLS output"));
+        }
+        if self.dash {
+            required.push((Feature::Muxer("dash"), "DASH output"));
+        }
+        required
+    }
+
+    /// The rungs encoded from a render of the given height
+    ///
+    /// Rungs taller than the render are skipped, since scaling up only spends bandwidth. A
+    /// render shorter than every rung gets one rung at its own height, with the lowest rung's bitrates.
+    ///
+    /// Example (default ladder, 720 high): [720p, 480p]
+    fn ladder_for(&self, source_height: u32) -> Vec<Rendition> {
+        let ladder: Vec<Rendition> = self.renditions.iter()
+            .filter(|r| r.height <= source_height)
+            .copied()
+            .collect();
+        if !ladder.is_empty() {
+            return ladder;
+        }
+        self.renditions.iter()
+            .min_by_key(|r| r.height)
+            .map(|lowest| vec![Rendition { height: source_height / 2 * 2, ..*lowest }])
+            .unwrap_or_default()
+    }
+
+    /// Encoder arguments of a rendition, up to the output path
+    ///
+    /// Constrained VBR (maxrate 1.07x, 1.5x buffer) keeps each rung close to its advertised
+    /// bandwidth; keyframes on every segment boundary let players switch rungs there.
+    ///
+    /// Example: ["-vf", "scale=-2:720", "-c:v", "libx264", "-b:v", "2800k", "-maxrate", "2996k", "-bufsize", "4200k", ...]
+    fn rendition_args(&self, rendition: &Rendition) -> Vec<String> {
+        let bitrate = rendition.video_bitrate_kbps;
+        vec![
+            "-vf".to_string(),
+            format!("scale=-2:{}", rendition.height),
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-preset".to_string(),
+            "medium".to_string(),
+            "-b:v".to_string(),
+            format!("{}k", bitrate),
+            "-maxrate".to_string(),
+            format!("{}k", bitrate * 107 / 100),
+            "-bufsize".to_string(),
+            format!("{}k", bitrate * 3 / 2),
+            "-force_key_frames".to_string(),
+            format!("expr:gte(t,n_forced*{})", self.segment_duration),
+            "-sc_threshold".to_string(),
+            "0".to_string(),
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-b:a".to_string(),
+            format!("{}k", rendition.audio_bitrate_kbps),
+            "-ac".to_string(),
+            "2".to_string(),
+        ]
+    }
+}
+
+impl ExportPipeline {
+    /// Render the timeline and package it as a bitrate ladder into the directory settings.output_path
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn render_streaming(
+        &self,
+        clips: Vec<ClipData>,
+        transitions: Vec<TransitionData>,
+        text_overlays: Vec<TextOverlayData>,
+        redactions: Vec<RedactionData>,
+        tracks: Vec<TrackData>,
+        settings: ExportSettings,
+        streaming: &StreamingSettings,
+    ) -> Result<(ExportRoute, f64), String> {
+        streaming.validate()?;
+        eprintln!(
+            "📡 Exporting {:?} ladder (HLS: {}, DASH: {}) to {}",
+            streaming.renditions.iter().map(|r| r.name()).collect::<Vec<_>>(), streaming.hls, streaming.dash, settings.output_path
+        );
+
+        let ffmpeg = self.ffmpeg.lock()
+            .map_err(|e| format!("Failed to lock FFmpeg: {}", e))?
+            .clone();
+
+        ffmpeg.require(&streaming.required_features())?;
+
+        let workspace = JobWorkspace::create("streaming")?;
+        let video_path = workspace.file("streaming_source.mp4").display().to_string();
+        let directory = Path::new(&settings.output_path).to_path_buf();
+        let video_settings = ExportSettings {
+            output_path: video_path.clone(),
+            streaming: None,
+            ..settings
+        };
+        let rendered = self.render_timeline(clips, transitions, text_overlays, redactions, tracks, video_settings)?;
+
+        // Dry runs render nothing to probe, so they plan the ladder as requested
+        let ladder = if ffmpeg.is_recording() {
+            streaming.renditions.clone()
+        } else {
+            let source_height = ffmpeg.probe(&video_path)?.height;
+            let ladder = streaming.ladder_for(source_height);
+            if ladder != streaming.renditions {
+                eprintln!("   ⚠️  Export is {} pixels high, encoding {:?} only", source_height, ladder.iter().map(|r| r.name()).collect::<Vec<_>>());
+            }
+            ladder
+        };
+        let names: Vec<String> = ladder.iter().map(|r| r.name()).collect();
+
+        if !ffmpeg.is_recording() {
+            for name in &names {
+                std::fs::create_dir_all(directory.join(name))
+                    .map_err(|e| format!("Failed to create streaming directory: {}", e))?;
+            }
+        }
+
+        // Each rendition is encoded once; the muxers below only copy
+        let mut rendition_paths = Vec::with_capacity(ladder.len());
+        for (index, rendition) in ladder.iter().enumerate() {
+            ffmpeg.set_progress(
+                80.0 + (index as f64 / ladder.len() as f64) * 15.0,
+                format!("Encoding {} rendition...", rendition.name()),
+                None
+            );
+            let path = workspace.file(&format!("rendition_{}.mp4", rendition.name())).display().to_string();
+            let mut args = vec!["-i".to_string(), video_path.clone()];
+            args.extend(streaming.rendition_args(rendition));
+            args.push("-y".to_string());
+            args.push(path.clone());
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+            rendition_paths.push(path);
+        }
+
+        let inputs: Vec<String> = rendition_paths.iter()
+            .flat_map(|path| ["-i".to_string(), path.clone()])
+            .collect();
+        let segment_duration = format!("{}", streaming.segment_duration);
+
+        if streaming.hls {
+            ffmpeg.set_progress(95.0, "Packaging HLS...".to_string(), None);
+            // Every variant carries its own audio so each playlist plays on its own
+            let mut args = inputs.clone();
+            for index in 0..rendition_paths.len() {
+                args.extend(["-map".to_string(), format!("{}:v", index), "-map".to_string(), format!("{}:a", index)]);
+            }
+            let stream_map = names.iter()
+                .enumerate()
+                .map(|(index, name)| format!("v:{i},a:{i},name:{n}", i = index, n = name))
+                .collect::<Vec<_>>()
+                .join(" ");
+            args.extend(["-c", "copy", "-f", "hls", "-hls_time"].iter().map(|s| s.to_string()));
+            args.push(segment_duration.clone());
+            args.extend(["-hls_playlist_type", "vod", "-hls_segment_filename"].iter().map(|s| s.to_string()));
+            args.push(directory.join("%v").join("segment_%05d.ts").display().to_string());
+            args.extend(["-master_pl_name", MASTER_PLAYLIST, "-var_stream_map"].iter().map(|s| s.to_string()));
+            args.push(stream_map);
+            args.push("-y".to_string());
+            args.push(directory.join("%v").join("playlist.m3u8").display().to_string());
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+        }
+
+        if streaming.dash {
+            ffmpeg.set_progress(98.0, "Packaging DASH...".to_string(), None);
+            // One audio representation is shared by all video representations
+            let mut args = inputs;
+            for index in 0..rendition_paths.len() {
+                args.extend(["-map".to_string(), format!("{}:v", index)]);
+            }
+            args.extend(["-map", "0:a", "-c", "copy", "-f", "dash", "-seg_duration"].iter().map(|s| s.to_string()));
+            args.push(segment_duration);
+            args.extend([
+                "-use_template", "1",
+                "-use_timeline", "1",
+                "-adaptation_sets", "id=0,streams=v id=1,streams=a",
+                "-y",
+            ].iter().map(|s| s.to_string()));
+            args.push(directory.join(DASH_MANIFEST).display().to_string());
+
+            let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            ffmpeg.execute_command(&args_refs)?;
+        }
+
+        ffmpeg.set_progress(100.0, "Complete!".to_string(), None);
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_ladder() {
+        let streaming: StreamingSettings = serde_json::from_str("{}").unwrap();
+        assert!(streaming.validate().is_ok());
+        assert!(streaming.hls && !streaming.dash);
+        assert_eq!(streaming.renditions.iter().map(|r| r.name()).collect::<Vec<_>>(), vec!["1080p", "720p", "480p"]);
+
+        let args = streaming.rendition_args(&streaming.renditions[1]);
+        let value = |flag: &str| args[args.iter().position(|a| a == flag).unwrap() + 1].clone();
+        assert_eq!(value("-vf"), "scale=-2:720");
+        assert_eq!((value("-maxrate"), value("-bufsize")), ("2996k".to_string(), "4200k".to_string()));
+        assert_eq!(value("-force_key_frames"), "expr:gte(t,n_forced*6)");
+    }
+
+    #[test]
+    fn test_ladder_never_scales_up() {
+        let streaming: StreamingSettings = serde_json::from_str("{}").unwrap();
+        let names = |ladder: Vec<Rendition>| ladder.iter().map(|r| r.name()).collect::<Vec<_>>();
+        assert_eq!(names(streaming.ladder_for(2160)), vec!["1080p", "720p", "480p"]);
+        assert_eq!(names(streaming.ladder_for(720)), vec!["720p", "480p"]);
+
+        // Shorter than every rung: one rung at the (even) source height, at the lowest bitrates
+        let ladder = streaming.ladder_for(361);
+        assert_eq!(names(ladder.clone()), vec!["360p"]);
+        assert_eq!((ladder[0].video_bitrate_kbps, ladder[0].audio_bitrate_kbps), (1400, 96));
+    }
+
+    #[test]
+    fn test_invalid_ladders() {
+        let valid: StreamingSettings = serde_json::from_str("{}").unwrap();
+        let mut streaming = valid.clone();
+        streaming.hls = false;
+        assert!(streaming.validate().is_err(), "Neither HLS nor DASH");
+
+        let mut streaming = valid.clone();
+        streaming.renditions.push(streaming.renditions[0]);
+        assert!(streaming.validate().is_err(), "Duplicate rendition");
+
+        let mut streaming = valid;
+        streaming.renditions[2].height = 481;
+        assert!(streaming.validate().is_err(), "Odd height");
+    }
+}
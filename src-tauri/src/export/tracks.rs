@@ -0,0 +1,165 @@
+// Track-level mixing and visibility for export
+use serde::{Deserialize, Serialize};
+
+/// Track settings from the timeline (sent from frontend)
+/// Tracks without an entry use the defaults: visible, audible, 0 dB, centered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackData {
+    pub index: u32,         // Track index (matches ClipData.track)
+    #[serde(default)]
+    pub muted: bool,        // Audio of this track is left out of the mix
+    #[serde(default)]
+    pub solo: bool,         // When any track is soloed, only soloed tracks are heard
+    #[serde(default)]
+    pub hidden: bool,       // Video of this track is left out of the picture (audio still plays)
+    #[serde(default)]
+    pub locked: bool,       // Editing lock only, has no effect on export
+    #[serde(default)]
+    pub gain_db: f64,       // Track gain in dB (0 = unchanged)
+    #[serde(default)]
+    pub pan: f64,           // Stereo balance: -1.0 = left, 0.0 = center, 1.0 = right
+    #[serde(default)]
+    pub tag: Option<String>, // Stem group the track belongs to (e.g. "voice", "music", "sfx")
+}
+
+/// Gain range accepted for a track
+const MIN_GAIN_DB: f64 = -60.0;
+const MAX_GAIN_DB: f64 = 24.0;
+
+/// Validate track settings: one entry per track, gain and pan in range
+pub fn validate(tracks: &[TrackData]) -> Result<(), String> {
+    for (i, track) in tracks.iter().enumerate() {
+        if tracks[..i].iter().any(|t| t.index == track.index) {
+            return Err(format!("Track {} is listed more than once", track.index));
+        }
+        if !track.gain_db.is_finite() || track.gain_db < MIN_GAIN_DB || track.gain_db > MAX_GAIN_DB {
+            return Err(format!(
+                "Track {} gain must be between {} and {} dB, got {}",
+                track.index, MIN_GAIN_DB, MAX_GAIN_DB, track.gain_db
+            ));
+        }
+        if !track.pan.is_finite() || track.pan < -1.0 || track.pan > 1.0 {
+            return Err(format!("Track {} pan must be between -1.0 and 1.0, got {}", track.index, track.pan));
+        }
+    }
+    Ok(())
+}
+
+fn find(tracks: &[TrackData], index: u32) -> Option<&TrackData> {
+    tracks.iter().find(|t| t.index == index)
+}
+
+/// Whether clips on this track contribute video
+pub fn is_visible(tracks: &[TrackData], index: u32) -> bool {
+    !find(tracks, index).is_some_and(|t| t.hidden)
+}
+
+/// Whether clips on this track contribute audio (mute, then solo)
+pub fn is_audible(tracks: &[TrackData], index: u32) -> bool {
+    let any_solo = tracks.iter().any(|t| t.solo);
+    match find(tracks, index) {
+        Some(track) => !track.muted && (!any_solo || track.solo),
+        None => !any_solo,
+    }
+}
+
+/// FFmpeg audio filters for a track's gain and pan
+/// Empty when the track is at unity gain and centered
+///
+/// Pan is a balance control: the far side is attenuated, the near side stays at unity.
+/// Example: gain_db -6, pan 0.5 -> ["volume=-6.00dB", "aformat=channel_layouts=stereo", "pan=stereo|c0=0.500*c0|c1=1.000*c1"]
+pub fn audio_filters(tracks: &[TrackData], index: u32) -> Vec<String> {
+    let mut filters = Vec::new();
+    let track = match find(tracks, index) {
+        Some(track) => track,
+        None => return filters,
+    };
+
+    if track.gain_db != 0.0 {
+        filters.push(format!("volume={:.2}dB", track.gain_db));
+    }
+
+    if track.pan != 0.0 {
+        let left = (1.0 - track.pan).min(1.0);
+        let right = (1.0 + track.pan).min(1.0);
+        filters.push("aformat=channel_layouts=stereo".to_string());
+        filters.push(format!("pan=stereo|c0={:.3}*c0|c1={:.3}*c1", left, right));
+    }
+
+    filters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(index: u32) -> TrackData {
+        TrackData {
+            index,
+            muted: false,
+            solo: false,
+            hidden: false,
+            locked: false,
+            gain_db: 0.0,
+            pan: 0.0,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn test_mute_and_solo() {
+        let mut tracks = vec![track(0), track(1), track(2)];
+        assert!(is_audible(&tracks, 0));
+        assert!(is_audible(&tracks, 5), "Tracks without settings are audible");
+
+        tracks[1].muted = true;
+        assert!(!is_audible(&tracks, 1));
+
+        // Soloing track 2 silences everything else, including tracks without settings
+        tracks[2].solo = true;
+        assert!(!is_audible(&tracks, 0));
+        assert!(is_audible(&tracks, 2));
+        assert!(!is_audible(&tracks, 5));
+
+        // Mute wins over solo
+        tracks[2].muted = true;
+        assert!(!is_audible(&tracks, 2));
+
+        tracks[0].hidden = true;
+        assert!(!is_visible(&tracks, 0));
+        assert!(is_visible(&tracks, 1));
+    }
+
+    #[test]
+    fn test_gain_and_pan_filters() {
+        let mut tracks = vec![track(0)];
+        assert!(audio_filters(&tracks, 0).is_empty());
+
+        tracks[0].gain_db = -6.0;
+        tracks[0].pan = -0.25;
+        assert_eq!(
+            audio_filters(&tracks, 0),
+            vec![
+                "volume=-6.00dB".to_string(),
+                "aformat=channel_layouts=stereo".to_string(),
+                "pan=stereo|c0=1.000*c0|c1=0.750*c1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_tracks() {
+        let mut tracks = vec![track(0), track(1)];
+        assert!(validate(&tracks).is_ok());
+
+        tracks[1].pan = 1.5;
+        assert!(validate(&tracks).is_err());
+
+        tracks[1].pan = 0.0;
+        tracks[1].gain_db = 30.0;
+        assert!(validate(&tracks).is_err());
+
+        tracks[1] = track(0);
+        assert!(validate(&tracks).is_err(), "Duplicate track index should be rejected");
+    }
+}
@@ -0,0 +1,384 @@
+// Transition library: maps frontend transition types and options to FFmpeg xfade, and
+// joins the segments of a sequential track with them
+use serde::{Deserialize, Serialize};
+
+use super::pipeline::TransitionData;
+
+/// Optional per-transition parameters (sent from frontend)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransitionOptions {
+    pub direction: Option<String>,  // Direction the transition edge moves (left, right, up, down, ...)
+    pub softness: Option<f64>,      // Edge softness for wipes, 0.0 (hard) to 1.0 (very soft)
+    pub color: Option<String>,      // Color to fade through (#RRGGBB)
+    pub expression: Option<String>, // Custom xfade expression (custom transition only)
+}
+
+/// Transition description returned to the frontend by list_transitions
+#[derive(Debug, Clone, Serialize)]
+pub struct TransitionInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub directions: Vec<&'static str>,  // Allowed direction values (empty = not directional)
+    pub softness: bool,                 // Accepts a softness option
+    pub color: bool,                    // Accepts a color option
+    pub expression: bool,               // Requires a custom expression
+}
+
+/// Catalog entry describing one transition type
+struct TransitionDef {
+    id: &'static str,
+    name: &'static str,
+    // (direction, xfade transition name); a single "" direction for non-directional transitions
+    variants: &'static [(&'static str, &'static str)],
+    softness: bool,
+    color: bool,
+    expression: bool,
+}
+
+const FOUR_WAY_WIPE: &[(&str, &str)] = &[
+    ("left", "wipeleft"), ("right", "wiperight"), ("up", "wipeup"), ("down", "wipedown"),
+    ("topLeft", "wipetl"), ("topRight", "wipetr"), ("bottomLeft", "wipebl"), ("bottomRight", "wipebr"),
+];
+
+/// All supported transitions. The first entries keep the original frontend names.
+const TRANSITIONS: &[TransitionDef] = &[
+    TransitionDef { id: "fade", name: "Fade", variants: &[("", "fade")], softness: false, color: false, expression: false },
+    TransitionDef { id: "crossfade", name: "Crossfade", variants: &[("", "fade")], softness: false, color: false, expression: false },
+    TransitionDef { id: "dissolve", name: "Dissolve", variants: &[("", "fade")], softness: false, color: false, expression: false },
+    TransitionDef { id: "fadeToBlack", name: "Fade to Black", variants: &[("", "fadeblack")], softness: false, color: false, expression: false },
+    // Wipe left = new clip comes from left, so the edge moves right (and vice versa)
+    TransitionDef { id: "wipeLeft", name: "Wipe Left", variants: &[("", "wiperight")], softness: false, color: false, expression: false },
+    TransitionDef { id: "wipeRight", name: "Wipe Right", variants: &[("", "wipeleft")], softness: false, color: false, expression: false },
+    TransitionDef { id: "fadeToWhite", name: "Fade to White", variants: &[("", "fadewhite")], softness: false, color: false, expression: false },
+    TransitionDef { id: "fadeThroughColor", name: "Fade Through Color", variants: &[("", "custom")], softness: false, color: true, expression: false },
+    TransitionDef { id: "fadeGrays", name: "Fade Through Grayscale", variants: &[("", "fadegrays")], softness: false, color: false, expression: false },
+    TransitionDef { id: "noiseDissolve", name: "Noise Dissolve", variants: &[("", "dissolve")], softness: false, color: false, expression: false },
+    TransitionDef { id: "wipe", name: "Wipe", variants: FOUR_WAY_WIPE, softness: true, color: false, expression: false },
+    TransitionDef {
+        id: "slide", name: "Slide",
+        variants: &[("left", "slideleft"), ("right", "slideright"), ("up", "slideup"), ("down", "slidedown")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "smoothWipe", name: "Smooth Wipe",
+        variants: &[("left", "smoothleft"), ("right", "smoothright"), ("up", "smoothup"), ("down", "smoothdown")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "diagonal", name: "Diagonal",
+        variants: &[("topLeft", "diagtl"), ("topRight", "diagtr"), ("bottomLeft", "diagbl"), ("bottomRight", "diagbr")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "slice", name: "Slice",
+        variants: &[("left", "hlslice"), ("right", "hrslice"), ("up", "vuslice"), ("down", "vdslice")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "open", name: "Barn Door Open",
+        variants: &[("horizontal", "horzopen"), ("vertical", "vertopen")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "close", name: "Barn Door Close",
+        variants: &[("horizontal", "horzclose"), ("vertical", "vertclose")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef {
+        id: "squeeze", name: "Squeeze",
+        variants: &[("horizontal", "squeezeh"), ("vertical", "squeezev")],
+        softness: false, color: false, expression: false,
+    },
+    TransitionDef { id: "circleOpen", name: "Circle Open", variants: &[("", "circleopen")], softness: false, color: false, expression: false },
+    TransitionDef { id: "circleClose", name: "Circle Close", variants: &[("", "circleclose")], softness: false, color: false, expression: false },
+    TransitionDef { id: "circleCrop", name: "Circle Crop", variants: &[("", "circlecrop")], softness: false, color: false, expression: false },
+    TransitionDef { id: "rectCrop", name: "Rectangle Crop", variants: &[("", "rectcrop")], softness: false, color: false, expression: false },
+    TransitionDef { id: "radial", name: "Radial", variants: &[("", "radial")], softness: false, color: false, expression: false },
+    TransitionDef { id: "pixelize", name: "Pixelize", variants: &[("", "pixelize")], softness: false, color: false, expression: false },
+    TransitionDef { id: "zoomIn", name: "Zoom In", variants: &[("", "zoomin")], softness: false, color: false, expression: false },
+    TransitionDef { id: "distance", name: "Distance", variants: &[("", "distance")], softness: false, color: false, expression: false },
+    TransitionDef { id: "blur", name: "Blur", variants: &[("", "hblur")], softness: false, color: false, expression: false },
+    TransitionDef { id: "custom", name: "Custom Expression", variants: &[("", "custom")], softness: false, color: false, expression: true },
+];
+
+/// List all supported transitions with their accepted options
+pub fn list_transitions() -> Vec<TransitionInfo> {
+    TRANSITIONS
+        .iter()
+        .map(|def| TransitionInfo {
+            id: def.id,
+            name: def.name,
+            directions: def.variants.iter()
+                .map(|(direction, _)| *direction)
+                .filter(|direction| !direction.is_empty())
+                .collect(),
+            softness: def.softness,
+            color: def.color,
+            expression: def.expression,
+        })
+        .collect()
+}
+
+/// Validate a transition and build the xfade transition options for it
+///
+/// Returns the part of the xfade filter that selects the transition, e.g.
+/// "transition=slideleft" or "transition=custom:expr='...'"
+pub fn xfade_options(transition: &TransitionData) -> Result<String, String> {
+    let def = TRANSITIONS
+        .iter()
+        .find(|def| def.id == transition.transition_type)
+        .ok_or_else(|| format!("Unsupported transition type: {}", transition.transition_type))?;
+    let options = &transition.options;
+
+    if transition.duration <= 0.0 {
+        return Err(format!("Transition {} has invalid duration", transition.id));
+    }
+
+    // Resolve direction (defaults to the first variant)
+    let (direction, xfade_name) = match &options.direction {
+        Some(direction) => *def.variants
+            .iter()
+            .find(|(name, _)| !name.is_empty() && name == direction)
+            .ok_or_else(|| format!(
+                "Transition {} does not support direction '{}'",
+                def.id, direction
+            ))?,
+        None => def.variants[0],
+    };
+
+    if options.softness.is_some() && !def.softness {
+        return Err(format!("Transition {} does not support softness", def.id));
+    }
+    if options.color.is_some() && !def.color {
+        return Err(format!("Transition {} does not support a color", def.id));
+    }
+    if options.expression.is_some() && !def.expression {
+        return Err(format!("Transition {} does not support a custom expression", def.id));
+    }
+
+    // Soft-edged wipes are rendered with a custom expression
+    let softness = options.softness.unwrap_or(0.0);
+    if !(0.0..=1.0).contains(&softness) {
+        return Err(format!("Transition {} softness must be between 0 and 1", transition.id));
+    }
+    if softness > 0.0 {
+        return soft_wipe_expression(direction, softness)
+            .map(|expr| format!("transition=custom:expr='{}'", expr))
+            .ok_or_else(|| format!("Soft edges are only supported for left/right/up/down wipes, not '{}'", direction));
+    }
+
+    if def.color {
+        let color = options.color.as_deref().unwrap_or("#000000");
+        return Ok(format!("transition=custom:expr='{}'", fade_through_color_expression(color)?));
+    }
+
+    if def.expression {
+        let expression = options.expression.as_deref().unwrap_or("").trim();
+        // Expression is embedded in a quoted filtergraph option, so reject anything that could escape it
+        if expression.is_empty() || expression.contains(['\'', ';', '[', ']', '\\']) {
+            return Err(format!("Transition {} has an invalid custom expression", transition.id));
+        }
+        return Ok(format!("transition=custom:expr='{}'", expression));
+    }
+
+    Ok(format!("transition={}", xfade_name))
+}
+
+/// The transition from one clip to the next, if there is one
+pub fn between(transitions: &[TransitionData], before: u32, after: u32) -> Option<&TransitionData> {
+    transitions.iter().find(|t| t.clip_id_before == before && t.clip_id_after == after)
+}
+
+/// One segment of a sequential track, as pads of a filter graph
+#[derive(Debug, Clone)]
+pub struct TrackSegment {
+    pub video: String,          // Pad carrying the segment's video, e.g. "[v0]"
+    pub audio: Option<String>,  // Pad carrying its audio (None when the track's audio is mixed separately)
+    pub duration: f64,          // Length in seconds
+    pub clip_id: Option<u32>,   // Clip the segment shows (None for gaps)
+}
+
+/// Join the segments of a sequential track into video_out (and audio_out when they carry audio)
+///
+/// Clips joined by a transition are blended with xfade and acrossfade, so they overlap by
+/// the transition's duration; everything else is put together by one concat.
+///
+/// Example for a fade between two clips followed by a gap:
+/// "[v0][v1]xfade=transition=fade:duration=0.500:offset=1.500[vx1];[a0][a1]acrossfade=d=0.500[ax1];[vx1][ax1][v2][a2]concat=n=2:v=1:a=1[vbase][abase]"
+pub fn join_segments(
+    segments: &[TrackSegment],
+    transitions: &[TransitionData],
+    video_out: &str,
+    audio_out: &str,
+) -> Result<Vec<String>, String> {
+    if segments.is_empty() {
+        return Err("No segments to join".to_string());
+    }
+
+    let mut parts = Vec::new();
+    // (video pad, audio pad, duration) of each run of segments blended together
+    let mut runs: Vec<(String, Option<String>, f64)> = Vec::new();
+    for (n, segment) in segments.iter().enumerate() {
+        let previous_clip = n.checked_sub(1).and_then(|previous| segments[previous].clip_id);
+        let transition = match (previous_clip, segment.clip_id) {
+            (Some(before), Some(after)) => between(transitions, before, after),
+            _ => None,
+        };
+        let (Some(transition), Some(run)) = (transition, runs.last_mut()) else {
+            runs.push((segment.video.clone(), segment.audio.clone(), segment.duration));
+            continue;
+        };
+
+        parts.push(format!(
+            "{}{}xfade={}:duration={:.3}:offset={:.3}[vx{}]",
+            run.0, segment.video, xfade_options(transition)?, transition.duration, run.2 - transition.duration, n
+        ));
+        run.0 = format!("[vx{}]", n);
+        if let (Some(run_audio), Some(audio)) = (&run.1, &segment.audio) {
+            parts.push(format!("{}{}acrossfade=d={:.3}[ax{}]", run_audio, audio, transition.duration, n));
+            run.1 = Some(format!("[ax{}]", n));
+        }
+        run.2 += segment.duration - transition.duration;
+    }
+
+    let with_audio = runs.iter().all(|(_, audio, _)| audio.is_some());
+    let inputs: String = runs.iter()
+        .map(|(video, audio, _)| match audio {
+            Some(audio) if with_audio => format!("{}{}", video, audio),
+            _ => video.clone(),
+        })
+        .collect();
+    if with_audio {
+        parts.push(format!("{}concat=n={}:v=1:a=1{}{}", inputs, runs.len(), video_out, audio_out));
+    } else {
+        parts.push(format!("{}concat=n={}:v=1:a=0{}", inputs, runs.len(), video_out));
+    }
+    Ok(parts)
+}
+
+/// Build an xfade expression for a wipe with a soft edge
+///
+/// xfade's P runs from 1 (start) to 0 (end). The edge sweeps from -softness to 1 along the
+/// wipe axis u, and pixels within `softness` behind the edge blend between A and B.
+fn soft_wipe_expression(direction: &str, softness: f64) -> Option<String> {
+    // u = position along the wipe axis where the new clip appears first (0) to last (1)
+    let axis = match direction {
+        "left" => "(1-X/W)",
+        "right" => "(X/W)",
+        "up" => "(1-Y/H)",
+        "down" => "(Y/H)",
+        _ => return None,
+    };
+    let alpha = format!("clip(((1-P)*(1+{s})-{u})/{s},0,1)", s = softness, u = axis);
+    Some(format!("A*(1-{a})+B*{a}", a = alpha))
+}
+
+/// Build an xfade expression that fades A out to a solid color, then fades B in from it
+///
+/// Streams are normalized to yuv420p before xfade, so the color is converted to BT.601 YUV
+fn fade_through_color_expression(color: &str) -> Result<String, String> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    let channel = |range: std::ops::Range<usize>| {
+        hex.get(range)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(|v| v as f64 / 255.0)
+    };
+    let (r, g, b) = match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+        (6, Some(r), Some(g), Some(b)) => (r, g, b),
+        _ => return Err(format!("Invalid transition color: {}", color)),
+    };
+
+    let y = 16.0 + 65.481 * r + 128.553 * g + 24.966 * b;
+    let u = 128.0 - 37.797 * r - 74.203 * g + 112.0 * b;
+    let v = 128.0 + 112.0 * r - 93.786 * g - 18.214 * b;
+    let plane_color = format!("if(eq(PLANE,0),{:.1},if(eq(PLANE,1),{:.1},{:.1}))", y, u, v);
+
+    Ok(format!(
+        "if(gt(P,0.5),{c}+(A-{c})*(2*P-1),{c}+(B-{c})*(1-2*P))",
+        c = plane_color
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(transition_type: &str, options: TransitionOptions) -> TransitionData {
+        TransitionData {
+            id: 1,
+            clip_id_before: 1,
+            clip_id_after: 2,
+            transition_type: transition_type.to_string(),
+            duration: 1.0,
+            options,
+            audio_offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_legacy_transition_names() {
+        let spec = xfade_options(&transition("wipeLeft", TransitionOptions::default())).unwrap();
+        assert_eq!(spec, "transition=wiperight");
+        let spec = xfade_options(&transition("fadeToBlack", TransitionOptions::default())).unwrap();
+        assert_eq!(spec, "transition=fadeblack");
+    }
+
+    #[test]
+    fn test_transition_option_validation() {
+        let slide = TransitionOptions { direction: Some("up".to_string()), ..Default::default() };
+        assert_eq!(xfade_options(&transition("slide", slide)).unwrap(), "transition=slideup");
+
+        let bad_direction = TransitionOptions { direction: Some("sideways".to_string()), ..Default::default() };
+        assert!(xfade_options(&transition("slide", bad_direction)).is_err());
+
+        let soft_fade = TransitionOptions { softness: Some(0.2), ..Default::default() };
+        assert!(xfade_options(&transition("fade", soft_fade)).is_err());
+
+        let bad_color = TransitionOptions { color: Some("#12345".to_string()), ..Default::default() };
+        assert!(xfade_options(&transition("fadeThroughColor", bad_color)).is_err());
+
+        assert!(xfade_options(&transition("spin", TransitionOptions::default())).is_err());
+    }
+
+    #[test]
+    fn test_join_segments() {
+        let segment = |n: usize, duration: f64, clip_id: Option<u32>| TrackSegment {
+            video: format!("[v{}]", n),
+            audio: Some(format!("[a{}]", n)),
+            duration,
+            clip_id,
+        };
+        let segments = vec![segment(0, 2.0, Some(1)), segment(1, 2.0, Some(2)), segment(2, 1.0, None)];
+        let fade = TransitionData { duration: 0.5, ..transition("fade", TransitionOptions::default()) };
+
+        let parts = join_segments(&segments, &[fade], "[vbase]", "[abase]").unwrap();
+        assert_eq!(parts, vec![
+            "[v0][v1]xfade=transition=fade:duration=0.500:offset=1.500[vx1]",
+            "[a0][a1]acrossfade=d=0.500[ax1]",
+            "[vx1][ax1][v2][a2]concat=n=2:v=1:a=1[vbase][abase]",
+        ]);
+
+        // Without a transition the segments are only concatenated; separately mixed audio is left out
+        let video_only: Vec<TrackSegment> = segments.into_iter().map(|s| TrackSegment { audio: None, ..s }).collect();
+        let parts = join_segments(&video_only, &[], "[vbase]", "[abase]").unwrap();
+        assert_eq!(parts, vec!["[v0][v1][v2]concat=n=3:v=1:a=0[vbase]"]);
+    }
+
+    #[test]
+    fn test_custom_expression_transitions() {
+        let soft_wipe = TransitionOptions {
+            direction: Some("right".to_string()),
+            softness: Some(0.25),
+            ..Default::default()
+        };
+        let spec = xfade_options(&transition("wipe", soft_wipe)).unwrap();
+        assert!(spec.starts_with("transition=custom:expr='A*(1-clip("));
+
+        let white = TransitionOptions { color: Some("#FFFFFF".to_string()), ..Default::default() };
+        let spec = xfade_options(&transition("fadeThroughColor", white)).unwrap();
+        assert!(spec.contains("if(eq(PLANE,0),235.0,if(eq(PLANE,1),128.0,128.0))"));
+
+        let injected = TransitionOptions { expression: Some("A'[x]".to_string()), ..Default::default() };
+        assert!(xfade_options(&transition("custom", injected)).is_err());
+    }
+}
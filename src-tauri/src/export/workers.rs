@@ -0,0 +1,153 @@
+// Bounded worker pool for running independent FFmpeg jobs concurrently
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Upper bound on concurrent jobs when parallelism is chosen automatically
+/// Every job decodes and encodes with its own threads, so more workers mostly add contention
+const MAX_AUTO_WORKERS: usize = 4;
+
+/// Size of a worker pool: how many jobs run at once and how many threads each job gets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkerPool {
+    pub workers: usize,
+    pub threads_per_job: usize,     // Passed to FFmpeg as -threads
+}
+
+impl WorkerPool {
+    /// Size a pool for this machine
+    /// parallelism sets the number of workers; None uses half the CPUs (1 to MAX_AUTO_WORKERS)
+    pub fn new(parallelism: Option<usize>) -> Self {
+        let cpus = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self::for_cpus(parallelism, cpus)
+    }
+
+    /// Split cpus between the workers so concurrent jobs don't oversubscribe the machine
+    ///
+    /// Example: 8 CPUs, auto -> 4 workers with 2 threads each
+    pub fn for_cpus(parallelism: Option<usize>, cpus: usize) -> Self {
+        let cpus = cpus.max(1);
+        let workers = parallelism
+            .unwrap_or((cpus / 2).clamp(1, MAX_AUTO_WORKERS))
+            .max(1);
+        Self {
+            workers,
+            threads_per_job: (cpus / workers).max(1),
+        }
+    }
+
+    /// Run job(0..count) on up to `workers` threads and return the results in job order
+    ///
+    /// After a job fails no new jobs are started; jobs already running finish, then the
+    /// error of the first failed job (in job order) is returned. on_done is called after
+    /// each successful job with the number of jobs finished so far, for progress reporting.
+    pub fn run<T, F, P>(&self, count: usize, job: F, on_done: P) -> Result<Vec<T>, String>
+    where
+        T: Send,
+        F: Fn(usize) -> Result<T, String> + Sync,
+        P: Fn(usize) + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let results: Mutex<Vec<Option<Result<T, String>>>> = Mutex::new((0..count).map(|_| None).collect());
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.workers.min(count) {
+                scope.spawn(|| loop {
+                    if failed.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= count {
+                        break;
+                    }
+
+                    let result = job(index);
+                    if result.is_ok() {
+                        on_done(finished.fetch_add(1, Ordering::SeqCst) + 1);
+                    } else {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+                    if let Ok(mut results) = results.lock() {
+                        results[index] = Some(result);
+                    }
+                });
+            }
+        });
+
+        let results = results.into_inner()
+            .map_err(|e| format!("Failed to collect job results: {}", e))?;
+
+        // Jobs are handed out in order, so every job before a failed one has a result
+        let mut outputs = Vec::with_capacity(count);
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Some(Ok(output)) => outputs.push(output),
+                Some(Err(e)) => return Err(e),
+                None => return Err(format!("Job {} was not run", index)),
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pool_sizing() {
+        assert_eq!(WorkerPool::for_cpus(None, 8), WorkerPool { workers: 4, threads_per_job: 2 });
+        assert_eq!(WorkerPool::for_cpus(None, 32), WorkerPool { workers: 4, threads_per_job: 8 });
+        assert_eq!(WorkerPool::for_cpus(None, 1), WorkerPool { workers: 1, threads_per_job: 1 });
+        assert_eq!(WorkerPool::for_cpus(Some(3), 8), WorkerPool { workers: 3, threads_per_job: 2 });
+        assert_eq!(WorkerPool::for_cpus(Some(0), 8), WorkerPool { workers: 1, threads_per_job: 8 });
+    }
+
+    #[test]
+    fn test_run_is_bounded_and_ordered() {
+        let pool = WorkerPool { workers: 3, threads_per_job: 1 };
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let progress = Mutex::new(Vec::new());
+
+        let results = pool.run(
+            10,
+            |index| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(5));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(index * 2)
+            },
+            |done| progress.lock().unwrap().push(done),
+        );
+
+        assert_eq!(results, Ok((0..10).map(|i| i * 2).collect::<Vec<_>>()));
+        assert!(peak.load(Ordering::SeqCst) <= 3, "At most 3 jobs should run at once");
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort();
+        assert_eq!(progress, (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_run_stops_after_failure() {
+        let pool = WorkerPool { workers: 1, threads_per_job: 1 };
+        let started = AtomicUsize::new(0);
+
+        let result: Result<Vec<()>, String> = pool.run(
+            5,
+            |index| {
+                started.fetch_add(1, Ordering::SeqCst);
+                if index == 1 { Err("clip 1 failed".to_string()) } else { Ok(()) }
+            },
+            |_| {},
+        );
+
+        assert_eq!(result, Err("clip 1 failed".to_string()));
+        assert_eq!(started.load(Ordering::SeqCst), 2, "No jobs should start after the failure");
+    }
+}
@@ -0,0 +1,182 @@
+// FFmpeg build capabilities: version, encoders, filters and muxers
+//
+// Builds differ (a static build without libfreetype has no drawtext, an LGPL build has
+// no libx264), so exports check what they need against this instead of failing
+// mid-encode with raw stderr.
+use std::collections::BTreeSet;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// What the FFmpeg binary supports, parsed from -version, -encoders, -filters and -muxers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FFmpegCapabilities {
+    pub version: String,            // e.g. "6.1.1" (or a git describe for dev builds)
+    pub encoders: BTreeSet<String>,
+    pub filters: BTreeSet<String>,
+    pub muxers: BTreeSet<String>,
+}
+
+/// A single encoder, filter or muxer an operation depends on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    Encoder(&'static str),
+    Filter(&'static str),
+    Muxer(&'static str),
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feature::Encoder(name) => write!(f, "the {} encoder", name),
+            Feature::Filter(name) => write!(f, "the {} filter", name),
+            Feature::Muxer(name) => write!(f, "the {} muxer", name),
+        }
+    }
+}
+
+impl FFmpegCapabilities {
+    /// Parse the outputs of `ffmpeg -version`, `-encoders`, `-filters` and `-muxers`
+    pub fn parse(version: &str, encoders: &str, filters: &str, muxers: &str) -> Self {
+        Self {
+            version: parse_version(version),
+            encoders: parse_listing(encoders),
+            filters: parse_filters(filters),
+            muxers: parse_listing(muxers),
+        }
+    }
+
+    /// Parse the listings, failing when they yield no encoders
+    ///
+    /// Unparseable listings would make every check fail, so they are reported instead and
+    /// callers treat the capabilities as unknown (and don't cache them).
+    pub fn from_listings(version: &str, encoders: &str, filters: &str, muxers: &str) -> Result<Self, String> {
+        let capabilities = Self::parse(version, encoders, filters, muxers);
+        if capabilities.encoders.is_empty() {
+            return Err(format!("Could not read the encoders of FFmpeg {}", capabilities.version));
+        }
+        Ok(capabilities)
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Encoder(name) => self.encoders.contains(name),
+            Feature::Filter(name) => self.filters.contains(name),
+            Feature::Muxer(name) => self.muxers.contains(name),
+        }
+    }
+
+    /// Check every (feature, what needs it) pair, failing on the first unsupported one
+    ///
+    /// Example error: "This FFmpeg build (6.1.1) lacks the drawtext filter, needed for text overlays"
+    pub fn require(&self, requirements: &[(Feature, &str)]) -> Result<(), String> {
+        match requirements.iter().find(|(feature, _)| !self.supports(*feature)) {
+            Some((feature, purpose)) => Err(format!(
+                "This FFmpeg build ({}) lacks {}, needed for {}",
+                self.version, feature, purpose
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Version from the first line: "ffmpeg version 6.1.1 Copyright (c) 2000-2023 ..."
+fn parse_version(output: &str) -> String {
+    output.lines()
+        .next()
+        .and_then(|line| line.strip_prefix("ffmpeg version "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Names from an -encoders or -muxers listing
+///
+/// Entries follow a " ------" (or " --") separator and look like
+/// " V....D libx264   libx264 H.264 / AVC ..." or "  E mp4   MP4 (MPEG-4 Part 14)";
+/// the name is the second column.
+fn parse_listing(output: &str) -> BTreeSet<String> {
+    output.lines()
+        .skip_while(|line| !line.trim_start().starts_with("--"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .flat_map(|names| names.split(','))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Names from a -filters listing
+///
+/// There is no separator; entries are the lines whose third column is the pad
+/// description, e.g. " TSC drawtext   V->V   Draw text on top of video frames ..."
+fn parse_filters(output: &str) -> BTreeSet<String> {
+    output.lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            match columns.as_slice() {
+                [_, name, pads, ..] if pads.contains("->") => Some(name.to_string()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION: &str = "ffmpeg version 6.1.1 Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with gcc 13.2.0\n";
+
+    const ENCODERS: &str = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10 (codec h264)
+ V....D png                  PNG (Portable Network Graphics) image
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+
+    const FILTERS: &str = "Filters:
+  T.. = Timeline support
+  .S. = Slice threading
+ ... concat            N->N       Concatenate audio and video streams.
+ TSC drawbox           V->V       Draw a colored box on the input video.
+ ..C xfade             VV->V      Cross fade one video with another video.
+ ... anullsrc          |->A       Null audio source, return empty audio frames.
+";
+
+    const MUXERS: &str = "File formats:
+ D. = Demuxing supported
+ .E = Muxing supported
+ --
+  E matroska        Matroska
+  E mp4             MP4 (MPEG-4 Part 14)
+";
+
+    #[test]
+    fn test_parse_capabilities() {
+        let capabilities = FFmpegCapabilities::parse(VERSION, ENCODERS, FILTERS, MUXERS);
+        assert_eq!(capabilities.version, "6.1.1");
+        assert_eq!(capabilities.encoders.iter().collect::<Vec<_>>(), vec!["aac", "libx264", "png"]);
+        assert_eq!(capabilities.filters.iter().collect::<Vec<_>>(), vec!["anullsrc", "concat", "drawbox", "xfade"]);
+        assert_eq!(capabilities.muxers.iter().collect::<Vec<_>>(), vec!["matroska", "mp4"]);
+    }
+
+    #[test]
+    fn test_unparseable_listings_are_not_capabilities() {
+        assert!(FFmpegCapabilities::from_listings(VERSION, ENCODERS, FILTERS, MUXERS).is_ok());
+
+        // An empty set would reject every export for the rest of the process
+        let result = FFmpegCapabilities::from_listings(VERSION, "Encoders:\n (none)\n", FILTERS, MUXERS);
+        assert_eq!(result, Err("Could not read the encoders of FFmpeg 6.1.1".to_string()));
+    }
+
+    #[test]
+    fn test_require_reports_missing_feature() {
+        let capabilities = FFmpegCapabilities::parse(VERSION, ENCODERS, FILTERS, MUXERS);
+        assert_eq!(capabilities.require(&[(Feature::Encoder("libx264"), "video"), (Feature::Filter("xfade"), "transitions")]), Ok(()));
+        assert_eq!(
+            capabilities.require(&[(Feature::Encoder("aac"), "audio"), (Feature::Filter("drawtext"), "text overlays")]),
+            Err("This FFmpeg build (6.1.1) lacks the drawtext filter, needed for text overlays".to_string())
+        );
+    }
+}
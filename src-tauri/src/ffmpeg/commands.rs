@@ -1,4 +1,5 @@
 // Tauri commands for FFmpeg operations
+use super::capabilities::FFmpegCapabilities;
 use super::{FFmpegWrapper, VideoMetadata};
 use tauri::State;
 use std::sync::Mutex;
@@ -72,3 +73,10 @@ pub fn ffmpeg_concat_videos(
     let wrapper = state.get_wrapper()?;
     wrapper.concat_videos(&input_paths, &output_path)
 }
+
+/// Report the version, encoders, filters and muxers of the FFmpeg build
+#[tauri::command]
+pub fn get_ffmpeg_capabilities(state: State<FFmpegState>) -> Result<FFmpegCapabilities, String> {
+    let wrapper = state.get_wrapper()?;
+    wrapper.capabilities().cloned()
+}
@@ -12,6 +12,16 @@ pub struct VideoMetadata {
     pub bitrate: Option<u64>,    // Bitrate in bits/second
     pub audio_codec: Option<String>, // Audio codec (e.g., "aac")
     pub file_size: u64,          // File size in bytes
+    #[serde(default)]
+    pub pix_fmt: Option<String>,         // Video pixel format (e.g., "yuv420p")
+    #[serde(default)]
+    pub audio_sample_rate: Option<u32>,  // Audio sample rate in Hz
+    #[serde(default)]
+    pub audio_channels: Option<u32>,     // Audio channel count
+    #[serde(default)]
+    pub profile: Option<String>,         // Video codec profile (e.g., "High", "Main 10")
+    #[serde(default)]
+    pub level: Option<i32>,              // Video codec level as ffprobe reports it (e.g., 40 for H.264 4.0)
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,6 +46,11 @@ struct Stream {
     height: Option<u32>,
     r_frame_rate: Option<String>,
     avg_frame_rate: Option<String>,
+    pix_fmt: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    profile: Option<String>,
+    level: Option<i32>,
 }
 
 impl VideoMetadata {
@@ -108,10 +123,37 @@ impl VideoMetadata {
             bitrate,
             audio_codec: audio_stream.map(|s| s.codec_name.clone()),
             file_size,
+            pix_fmt: video_stream.pix_fmt.clone(),
+            audio_sample_rate: audio_stream
+                .and_then(|s| s.sample_rate.as_ref())
+                .and_then(|r| r.parse::<u32>().ok()),
+            audio_channels: audio_stream.and_then(|s| s.channels),
+            profile: video_stream.profile.clone(),
+            level: video_stream.level.filter(|&level| level > 0),
         })
     }
 }
 
+/// Parse keyframe timestamps from ffprobe packet output
+/// Expects CSV lines of "pts_time,flags" (e.g., "4.004000,K__"); non-keyframe packets are skipped
+pub fn parse_keyframe_packets(output: &str) -> Vec<f64> {
+    let mut keyframes: Vec<f64> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().split(',');
+            let pts = fields.next()?.parse::<f64>().ok()?;
+            let flags = fields.next()?;
+            if flags.starts_with('K') {
+                Some(pts)
+            } else {
+                None
+            }
+        })
+        .collect();
+    keyframes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    keyframes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,11 +173,16 @@ mod tests {
                     "codec_name": "h264",
                     "width": 1920,
                     "height": 1080,
-                    "r_frame_rate": "30/1"
+                    "r_frame_rate": "30/1",
+                    "pix_fmt": "yuv420p",
+                    "profile": "High",
+                    "level": 40
                 },
                 {
                     "codec_type": "audio",
-                    "codec_name": "aac"
+                    "codec_name": "aac",
+                    "sample_rate": "48000",
+                    "channels": 2
                 }
             ]
         }"#;
@@ -147,5 +194,16 @@ mod tests {
         assert_eq!(metadata.codec, "h264");
         assert_eq!(metadata.fps, 30.0);
         assert_eq!(metadata.audio_codec, Some("aac".to_string()));
+        assert_eq!(metadata.pix_fmt, Some("yuv420p".to_string()));
+        assert_eq!(metadata.audio_sample_rate, Some(48000));
+        assert_eq!(metadata.audio_channels, Some(2));
+        assert_eq!(metadata.profile, Some("High".to_string()));
+        assert_eq!(metadata.level, Some(40));
+    }
+
+    #[test]
+    fn test_parse_keyframe_packets() {
+        let output = "2.002000,K__\n2.035367,___\nN/A,___\n0.000000,K_\n\n4.004000,K__\n";
+        assert_eq!(parse_keyframe_packets(output), vec![0.0, 2.002, 4.004]);
     }
 }
@@ -2,8 +2,11 @@
 // Provides video metadata extraction, thumbnail generation, and export functionality
 
 pub mod wrapper;
+pub mod capabilities;
 pub mod metadata;
 pub mod commands;
+pub mod recorder;
+pub mod workspace;
 
 pub use wrapper::FFmpegWrapper;
 pub use metadata::VideoMetadata;
@@ -0,0 +1,103 @@
+// Recording of FFmpeg invocations for dry runs
+//
+// A wrapper with a recorder attached logs every command instead of running it (see
+// FFmpegWrapper::recording). Concat demuxer lists are written by the pipeline just before
+// the command that reads them and removed right after, so their contents are captured
+// at record time to keep the recording reproducible.
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+
+/// A text file an invocation reads (concat demuxer list)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputFile {
+    pub path: String,
+    pub contents: String,
+}
+
+/// One FFmpeg invocation that would have run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub args: Vec<String>,
+    pub filtergraph: Option<String>,    // -filter_complex (or -vf) value, for reading and diffing
+    pub input_files: Vec<InputFile>,    // Lists that must exist before the command runs
+}
+
+impl RecordedCommand {
+    pub fn new(args: &[&str]) -> Self {
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|&arg| arg == flag)
+                .and_then(|index| args.get(index + 1))
+                .map(|value| value.to_string())
+        };
+
+        // Inputs ending in .txt are concat lists; read them while they still exist
+        let input_files = args.windows(2)
+            .filter(|pair| pair[0] == "-i" && pair[1].ends_with(".txt"))
+            .filter_map(|pair| {
+                let contents = std::fs::read_to_string(pair[1]).ok()?;
+                Some(InputFile { path: pair[1].to_string(), contents })
+            })
+            .collect();
+
+        Self {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            filtergraph: value_of("-filter_complex").or_else(|| value_of("-vf")),
+            input_files,
+        }
+    }
+
+    /// File written by the command (FFmpeg takes the output last)
+    pub fn output(&self) -> Option<&str> {
+        self.args.last().map(|arg| arg.as_str())
+    }
+}
+
+/// Shared log of recorded commands; clones append to the same log
+#[derive(Debug, Clone, Default)]
+pub struct CommandRecorder {
+    commands: Arc<Mutex<Vec<RecordedCommand>>>,
+}
+
+impl CommandRecorder {
+    pub fn record(&self, args: &[&str]) {
+        eprintln!("📝 Dry run, not running: ffmpeg {:?}", args);
+        if let Ok(mut commands) = self.commands.lock() {
+            commands.push(RecordedCommand::new(args));
+        }
+    }
+
+    /// Commands recorded so far, in the order they were issued
+    pub fn commands(&self) -> Vec<RecordedCommand> {
+        self.commands.lock()
+            .map(|commands| commands.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_command_captures_graph_and_lists() {
+        let dir = std::env::temp_dir().join(format!("clipforge_recorder_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("concat_list.txt");
+        std::fs::write(&list, "file '/work/clip_0_trimmed.mp4'").unwrap();
+        let list_str = list.display().to_string();
+
+        let command = RecordedCommand::new(&[
+            "-f", "concat", "-safe", "0", "-i", &list_str,
+            "-filter_complex", "[0:v]null[vout]", "-map", "[vout]", "-y", "/out/final.mp4",
+        ]);
+        assert_eq!(command.filtergraph.as_deref(), Some("[0:v]null[vout]"));
+        assert_eq!(command.output(), Some("/out/final.mp4"));
+        assert_eq!(
+            command.input_files,
+            vec![InputFile { path: list_str.clone(), contents: "file '/work/clip_0_trimmed.mp4'".to_string() }]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,167 @@
+// Per-job working directories for FFmpeg intermediates
+//
+// Every export (or other multi-step FFmpeg job) gets its own directory under
+// temp_dir()/clipforge_jobs, so concurrent jobs never share file names. The directory
+// is removed when the workspace is dropped: on success, on an early error return, and
+// when the job is abandoned by unwinding. Directories left behind by a crashed process
+// are swept on startup.
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Directory under the system temp dir holding all job workspaces
+const WORKSPACES_DIR: &str = "clipforge_jobs";
+
+/// File inside each workspace recording the owning process id
+const OWNER_FILE: &str = ".owner";
+
+/// Workspaces whose owner can't be checked are treated as orphaned after this long
+const ORPHAN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A unique working directory for one job, deleted on drop
+#[derive(Debug)]
+pub struct JobWorkspace {
+    dir: PathBuf,
+}
+
+impl JobWorkspace {
+    /// Create a workspace for a job; kind is a readable prefix (e.g. "export")
+    pub fn create(kind: &str) -> Result<Self, String> {
+        Self::create_in(&workspaces_root(), kind)
+    }
+
+    fn create_in(root: &Path, kind: &str) -> Result<Self, String> {
+        let dir = root.join(format!("{}_{}", kind, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create job workspace: {}", e))?;
+        std::fs::write(dir.join(OWNER_FILE), std::process::id().to_string())
+            .map_err(|e| format!("Failed to create job workspace: {}", e))?;
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of a file inside the workspace
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Drop for JobWorkspace {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            eprintln!("⚠️  Failed to remove job workspace {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+/// Directory holding every job workspace
+pub fn workspaces_root() -> PathBuf {
+    std::env::temp_dir().join(WORKSPACES_DIR)
+}
+
+/// Remove workspaces left behind by processes that are no longer running
+/// Returns the number of workspaces removed
+pub fn sweep_orphaned_workspaces() -> usize {
+    sweep_in(&workspaces_root())
+}
+
+fn sweep_in(root: &Path) -> usize {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut removed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let dir = entry.path();
+        if dir.is_dir() && is_orphaned(&dir) && std::fs::remove_dir_all(&dir).is_ok() {
+            eprintln!("🧹 Removed orphaned job workspace {}", dir.display());
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Whether a workspace's owner is gone
+///
+/// The owner is checked through /proc where it exists (Linux); elsewhere a workspace
+/// of another process counts as orphaned once it is older than ORPHAN_AGE.
+fn is_orphaned(dir: &Path) -> bool {
+    let owner = std::fs::read_to_string(dir.join(OWNER_FILE))
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok());
+
+    if owner == Some(std::process::id()) {
+        return false;
+    }
+    if let Some(pid) = owner {
+        let proc_dir = Path::new("/proc");
+        if proc_dir.is_dir() {
+            return !proc_dir.join(pid.to_string()).exists();
+        }
+    }
+
+    let age = std::fs::metadata(dir)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    age.is_some_and(|age| age > ORPHAN_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("clipforge_workspace_test_{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_workspaces_are_unique_and_removed_on_drop() {
+        let root = temp_root();
+        let first = JobWorkspace::create_in(&root, "export").unwrap();
+        let second = JobWorkspace::create_in(&root, "export").unwrap();
+        assert_ne!(first.path(), second.path());
+
+        std::fs::write(first.file("concat_list.txt"), "file 'a.mp4'").unwrap();
+        let first_dir = first.path().to_path_buf();
+
+        // Early return with an error drops the workspace too
+        let failing = || -> Result<(), String> {
+            let _workspace = JobWorkspace::create_in(&root, "failing")?;
+            Err("encode failed".to_string())
+        };
+        assert!(failing().is_err());
+
+        drop(first);
+        assert!(!first_dir.exists());
+        assert!(second.path().exists());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1, "Only the live workspace should remain");
+
+        drop(second);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_sweep_keeps_live_workspaces() {
+        let root = temp_root();
+        let live = JobWorkspace::create_in(&root, "export").unwrap();
+
+        // Workspace of a process that no longer exists; without /proc it is too new to sweep
+        let orphan = root.join("export_orphan");
+        std::fs::create_dir_all(&orphan).unwrap();
+        std::fs::write(orphan.join(OWNER_FILE), u32::MAX.to_string()).unwrap();
+
+        let removed = sweep_in(&root);
+        assert!(live.path().exists(), "Workspaces of this process must be kept");
+        if Path::new("/proc").is_dir() {
+            assert_eq!(removed, 1);
+            assert!(!orphan.exists());
+        }
+
+        drop(live);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,10 +1,13 @@
 // FFmpeg wrapper for executing FFmpeg and FFprobe commands
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use serde::{Deserialize, Serialize};
 
-use super::metadata::VideoMetadata;
+use super::capabilities::{FFmpegCapabilities, Feature};
+use super::metadata::{self, VideoMetadata};
+use super::recorder::CommandRecorder;
+use super::workspace::JobWorkspace;
 
 /// Progress tracking for export operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,10 +17,16 @@ pub struct ExportProgress {
     pub eta_seconds: Option<u64>,  // Estimated time remaining
 }
 
+/// Capabilities of the FFmpeg binary, probed once per process
+static CAPABILITIES: OnceLock<FFmpegCapabilities> = OnceLock::new();
+
+/// Clones share the progress state, so concurrent jobs report into the same export progress
+#[derive(Clone)]
 pub struct FFmpegWrapper {
     ffmpeg_path: PathBuf,
     ffprobe_path: PathBuf,
     progress: Arc<Mutex<ExportProgress>>,
+    recorder: Option<CommandRecorder>,     // Set for dry runs: execute_command records instead of running
 }
 
 impl FFmpegWrapper {
@@ -37,9 +46,24 @@ impl FFmpegWrapper {
                 current_operation: "Ready".to_string(),
                 eta_seconds: None,
             })),
+            recorder: None,
         })
     }
 
+    /// Copy of this wrapper that records FFmpeg commands instead of running them
+    /// FFprobe still runs, so sources are probed as in a real export
+    pub fn recording(&self, recorder: CommandRecorder) -> Self {
+        Self {
+            recorder: Some(recorder),
+            ..self.clone()
+        }
+    }
+
+    /// Whether commands are recorded instead of run (dry run)
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
     /// Get current export progress
     pub fn get_progress(&self) -> ExportProgress {
         self.progress.lock()
@@ -119,6 +143,36 @@ impl FFmpegWrapper {
         VideoMetadata::from_ffprobe_json(&stdout)
     }
 
+    /// List video keyframe timestamps (seconds) between start and end using ffprobe
+    /// Reads packet flags only, so no frames are decoded
+    pub fn probe_keyframes(&self, video_path: &str, start: f64, end: f64) -> Result<Vec<f64>, String> {
+        let interval = format!("{:.3}%{:.3}", start.max(0.0), end);
+        let output = Command::new(&self.ffprobe_path)
+            .args(&[
+                "-v",
+                "quiet",
+                "-select_streams",
+                "v:0",
+                "-read_intervals",
+                &interval,
+                "-show_entries",
+                "packet=pts_time,flags",
+                "-of",
+                "csv=p=0",
+                video_path,
+            ])
+            .output()
+            .map_err(|e| format!("Failed to execute ffprobe: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("FFprobe failed: {}", stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(metadata::parse_keyframe_packets(&stdout))
+    }
+
     /// Generate a thumbnail from a video at a specific timestamp
     /// timestamp: Time in seconds (e.g., 5.0 for 5 seconds into the video)
     pub fn generate_thumbnail(
@@ -196,18 +250,20 @@ impl FFmpegWrapper {
             return Err("No input videos provided for concatenation".to_string());
         }
 
-        // Create a temporary concat file list
+        // Create a temporary concat file list (escape single quotes in paths)
         // Format: file 'path/to/video1.mp4'\nfile 'path/to/video2.mp4'
         let concat_list = input_paths
             .iter()
-            .map(|p| format!("file '{}'", p))
+            .map(|p| format!("file '{}'", p.replace("'", "'\\''")))
             .collect::<Vec<_>>()
             .join("\n");
 
-        // Write concat list to temp file
-        let concat_file_path = "concat_list.txt";
-        std::fs::write(concat_file_path, concat_list)
+        // Write concat list into this job's own workspace (removed when it is dropped)
+        let workspace = JobWorkspace::create("concat")?;
+        let concat_file_path = workspace.file("concat_list.txt");
+        std::fs::write(&concat_file_path, concat_list)
             .map_err(|e| format!("Failed to write concat list: {}", e))?;
+        let concat_file_str = concat_file_path.display().to_string();
 
         let output = Command::new(&self.ffmpeg_path)
             .args(&[
@@ -216,7 +272,7 @@ impl FFmpegWrapper {
                 "-safe",
                 "0",
                 "-i",
-                concat_file_path,
+                &concat_file_str,
                 "-c",
                 "copy",
                 "-y",
@@ -227,9 +283,6 @@ impl FFmpegWrapper {
             .output()
             .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
 
-        // Clean up temp file
-        let _ = std::fs::remove_file(concat_file_path);
-
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(format!("FFmpeg concatenation failed: {}", stderr));
@@ -238,9 +291,50 @@ impl FFmpegWrapper {
         Ok(())
     }
 
+    /// Encoders, filters and muxers of the FFmpeg build
+    /// Probed on first use (warmed at startup) and cached for the rest of the process
+    pub fn capabilities(&self) -> Result<&'static FFmpegCapabilities, String> {
+        if let Some(capabilities) = CAPABILITIES.get() {
+            return Ok(capabilities);
+        }
+
+        let capabilities = FFmpegCapabilities::from_listings(
+            &self.run(&["-version"])?,
+            &self.run(&["-hide_banner", "-encoders"])?,
+            &self.run(&["-hide_banner", "-filters"])?,
+            &self.run(&["-hide_banner", "-muxers"])?,
+        )?;
+        eprintln!(
+            "🔍 FFmpeg {}: {} encoders, {} filters, {} muxers",
+            capabilities.version, capabilities.encoders.len(), capabilities.filters.len(), capabilities.muxers.len()
+        );
+        Ok(CAPABILITIES.get_or_init(|| capabilities))
+    }
+
+    /// Check the build has every feature an export needs, paired with what needs it
+    /// Skipped with a warning when the capabilities can't be probed
+    pub fn require(&self, requirements: &[(Feature, &str)]) -> Result<(), String> {
+        match self.capabilities() {
+            Ok(capabilities) => capabilities.require(requirements),
+            Err(e) => {
+                eprintln!("   ⚠️  Could not probe FFmpeg capabilities, skipping check: {}", e);
+                Ok(())
+            }
+        }
+    }
+
     /// Execute a custom FFmpeg command with arbitrary arguments
     /// Useful for complex operations not covered by other methods
     pub fn execute_command(&self, args: &[&str]) -> Result<String, String> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(args);
+            return Ok(String::new());
+        }
+        self.run(args)
+    }
+
+    /// Run FFmpeg and return its stdout
+    fn run(&self, args: &[&str]) -> Result<String, String> {
         let output = Command::new(&self.ffmpeg_path)
             .args(args)
             .stdout(Stdio::piped())
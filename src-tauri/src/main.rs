@@ -31,6 +31,17 @@ fn main() {
 
     let app_state = AppState { db: Arc::new(db) };
 
+    // Remove working directories left behind by exports of a crashed process
+    ffmpeg::workspace::sweep_orphaned_workspaces();
+
+    // Probe the FFmpeg build in the background so exports can check what it supports
+    std::thread::spawn(|| {
+        let probed = ffmpeg::FFmpegWrapper::new().and_then(|wrapper| wrapper.capabilities().map(|_| ()));
+        if let Err(e) = probed {
+            eprintln!("⚠️  FFmpeg capability probe failed: {}", e);
+        }
+    });
+
     // Initialize FFmpeg state
     let ffmpeg_state = FFmpegState::new();
 
@@ -42,13 +53,17 @@ fn main() {
             ffmpeg::commands::ffmpeg_generate_thumbnail,
             ffmpeg::commands::ffmpeg_trim_video,
             ffmpeg::commands::ffmpeg_concat_videos,
+            ffmpeg::commands::get_ffmpeg_capabilities,
             commands::import::import_video,
             commands::import::get_media_library,
             commands::import::delete_media_item,
             commands::recording::save_recording,
             commands::recording::import_recording,
             commands::export::export_timeline,
+            commands::export::export_frame,
             commands::export::get_export_progress,
+            commands::export::list_transitions,
+            commands::export::clear_render_cache,
             commands::transcription::transcribe_timeline,
             commands::project::save_project,
             commands::project::load_project,
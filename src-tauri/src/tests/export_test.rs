@@ -6,9 +6,19 @@
 mod tests {
     use crate::export::{ExportPipeline, ExportSettings};
     use crate::export::encoder::Resolution;
-    use crate::export::pipeline::ClipData;
+    use crate::export::{GapFill, LayoutTemplate, MasterBus};
+    use crate::export::animation::{AnimationFormat, AnimationSettings, Dither};
+    use crate::export::audio::{AudioFormat, AudioSettings};
+    use crate::export::master::CompressorPreset;
+    use crate::export::pipeline::{ClipData, RedactionData, RedactionMode, TextOverlayData, TransitionData, ZoomRegionData};
+    use crate::export::plan::ExportRoute;
+    use crate::export::range::ExportRange;
+    use crate::export::sequence::{ImageSequenceSettings, SequenceFormat};
+    use crate::export::stems::{StemGrouping, StemSettings};
+    use crate::export::streaming::{Rendition, StreamingSettings};
+    use crate::export::tracks::TrackData;
     use crate::ffmpeg::FFmpegWrapper;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
 
     /// Test fixture path for test video
@@ -22,6 +32,37 @@ mod tests {
         std::env::temp_dir().join(format!("clipforge_test_{}.mp4", name))
     }
 
+    /// Duration of a rendered file according to ffprobe
+    fn probed_duration(path: &Path) -> f64 {
+        FFmpegWrapper::new().unwrap().probe(path.to_str().unwrap()).expect("Output should probe").duration
+    }
+
+    /// ffprobe entries of a file, one CSV line per stream or section
+    /// Works for audio-only files, which FFmpegWrapper::probe rejects
+    ///
+    /// Example: probe_entries(path, "stream=codec_type") -> ["video", "audio"]
+    fn probe_entries(path: &Path, entries: &str) -> Vec<String> {
+        let output = std::process::Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", entries, "-of", "csv=p=0", path.to_str().unwrap()])
+            .output()
+            .expect("Failed to run ffprobe");
+        assert!(output.status.success(), "Probing {} should succeed", path.display());
+        String::from_utf8_lossy(&output.stdout).lines().map(|line| line.trim().to_string()).collect()
+    }
+
+    /// Container duration of any rendered file, including audio-only ones
+    fn probed_format_duration(path: &Path) -> f64 {
+        probe_entries(path, "format=duration")[0].parse().expect("Duration should be a number")
+    }
+
+    /// Default export settings, without the render cache so each test renders its own segments
+    fn test_settings(resolution: Resolution, output_path: &Path) -> ExportSettings {
+        ExportSettings {
+            render_cache: false,
+            ..ExportSettings::new(resolution, output_path.to_str().unwrap().to_string())
+        }
+    }
+
     #[test]
     fn test_export_single_clip() {
         let test_video = get_test_video_path();
@@ -33,20 +74,10 @@ mod tests {
         let _ = std::fs::remove_file(&output_path);
 
         // Create clip data (2-second clip, no trimming)
-        let clips = vec![ClipData {
-            id: 1,
-            path: test_video.to_str().unwrap().to_string(),
-            in_point: 0.0,
-            out_point: 2.0,
-            start_time: 0.0,
-            track: 0,
-        }];
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0)];
 
         // Export settings (source resolution)
-        let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::Source, &output_path);
 
         // Create export pipeline
         let ffmpeg = Arc::new(Mutex::new(
@@ -55,7 +86,7 @@ mod tests {
         let pipeline = ExportPipeline::new(ffmpeg);
 
         // Execute export
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
 
         // Verify export succeeded
         assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
@@ -80,24 +111,14 @@ mod tests {
         let _ = std::fs::remove_file(&output_path);
 
         // Create clip data (trim to 1 second: 0.5s to 1.5s)
-        let clips = vec![ClipData {
-            id: 1,
-            path: test_video.to_str().unwrap().to_string(),
-            in_point: 0.5,
-            out_point: 1.5,
-            start_time: 0.0,
-            track: 0,
-        }];
+        let clips = vec![ClipData::fixture(1, 0.0, 0.5, 1.5)];
 
-        let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::Source, &output_path);
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
         assert!(result.is_ok(), "Trimmed export should succeed: {:?}", result.err());
         assert!(output_path.exists());
 
@@ -115,33 +136,16 @@ mod tests {
 
         // Create two clips concatenated (total 4 seconds)
         let clips = vec![
-            ClipData {
-                id: 1,
-                path: test_video.to_str().unwrap().to_string(),
-                in_point: 0.0,
-                out_point: 2.0,
-                start_time: 0.0,
-                track: 0,
-            },
-            ClipData {
-                id: 2,
-                path: test_video.to_str().unwrap().to_string(),
-                in_point: 0.0,
-                out_point: 2.0,
-                start_time: 2.0,
-                track: 0,
-            },
+            ClipData::fixture(1, 0.0, 0.0, 2.0),
+            ClipData::fixture(2, 2.0, 0.0, 2.0),
         ];
 
-        let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::Source, &output_path);
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
         assert!(result.is_ok(), "Multi-clip export should succeed: {:?}", result.err());
         assert!(output_path.exists());
 
@@ -161,25 +165,15 @@ mod tests {
         let output_path = get_test_output_path("720p");
         let _ = std::fs::remove_file(&output_path);
 
-        let clips = vec![ClipData {
-            id: 1,
-            path: test_video.to_str().unwrap().to_string(),
-            in_point: 0.0,
-            out_point: 2.0,
-            start_time: 0.0,
-            track: 0,
-        }];
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0)];
 
         // Export with 720p resolution
-        let settings = ExportSettings {
-            resolution: Resolution::HD720,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::HD720, &output_path);
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
         assert!(result.is_ok(), "720p export should succeed: {:?}", result.err());
         assert!(output_path.exists());
 
@@ -203,15 +197,12 @@ mod tests {
         // Empty clips array
         let clips = vec![];
 
-        let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::Source, &output_path);
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
 
         // Should fail with empty timeline
         assert!(result.is_err(), "Export with no clips should fail");
@@ -224,24 +215,14 @@ mod tests {
         let _ = std::fs::remove_file(&output_path);
 
         // Clip with nonexistent source file
-        let clips = vec![ClipData {
-            id: 1,
-            path: "/nonexistent/video.mp4".to_string(),
-            in_point: 0.0,
-            out_point: 2.0,
-            start_time: 0.0,
-            track: 0,
-        }];
+        let clips = vec![ClipData { path: "/nonexistent/video.mp4".to_string(), ..ClipData::fixture(1, 0.0, 0.0, 2.0) }];
 
-        let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
-        };
+        let settings = test_settings(Resolution::Source, &output_path);
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
 
         // Should fail due to missing source file
         assert!(result.is_err(), "Export with invalid source should fail");
@@ -257,37 +238,744 @@ mod tests {
 
         // Create clips on different tracks (track 0 = base, track 1 = overlay)
         let clips = vec![
-            ClipData {
+            ClipData::fixture(1, 0.0, 0.0, 2.0),                             // Base track
+            ClipData { track: 1, ..ClipData::fixture(2, 0.5, 0.0, 1.0) },    // Overlay track
+        ];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Multi-track export should succeed: {:?}", result.err());
+        assert!(output_path.exists());
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_multitrack_side_by_side_layout() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("multitrack_side_by_side");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Create clips on different tracks (track 0 = base, track 1 = overlay)
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 2.0),                             // Left-hand slot
+            ClipData { track: 1, ..ClipData::fixture(2, 0.5, 0.0, 1.0) },    // Right-hand slot
+        ];
+
+        let settings = ExportSettings {
+            layout: LayoutTemplate::SideBySide,
+            ..test_settings(Resolution::HD720, &output_path)
+        };
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Side-by-side export should succeed: {:?}", result.err());
+        assert!(output_path.exists());
+
+        // Tiled layouts render onto the export canvas
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert_eq!(metadata.width, 1280);
+        assert_eq!(metadata.height, 720);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_redaction() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("redaction");
+        let _ = std::fs::remove_file(&output_path);
+
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0)];
+
+        // One region per mode, overlapping in time
+        let redactions = vec![
+            RedactionData {
                 id: 1,
-                path: test_video.to_str().unwrap().to_string(),
-                in_point: 0.0,
-                out_point: 2.0,
                 start_time: 0.0,
-                track: 0, // Base track
+                duration: 1.0,
+                x: 10.0,
+                y: 10.0,
+                width: 30.0,
+                height: 20.0,
+                mode: RedactionMode::Blur,
+                strength: 20,
+                color: "#000000".to_string(),
             },
-            ClipData {
+            RedactionData {
                 id: 2,
-                path: test_video.to_str().unwrap().to_string(),
-                in_point: 0.0,
-                out_point: 1.0,
                 start_time: 0.5,
-                track: 1, // Overlay track
+                duration: 1.5,
+                x: 50.0,
+                y: 50.0,
+                width: 40.0,
+                height: 40.0,
+                mode: RedactionMode::Pixelate,
+                strength: 16,
+                color: "#000000".to_string(),
+            },
+            RedactionData {
+                id: 3,
+                start_time: 1.0,
+                duration: 1.0,
+                x: 0.0,
+                y: 80.0,
+                width: 100.0,
+                height: 20.0,
+                mode: RedactionMode::Box,
+                strength: 0,
+                color: "#FF0000".to_string(),
             },
         ];
 
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], redactions, vec![], settings);
+        assert!(result.is_ok(), "Export with redactions should succeed: {:?}", result.err());
+        assert!(output_path.exists());
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_zoom_region() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("zoom_region");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Punch into the top-left quarter for the middle of the clip
+        let clips = vec![ClipData {
+            zoom_regions: vec![ZoomRegionData {
+                start_time: 0.25,
+                duration: 1.5,
+                x: 0.0,
+                y: 0.0,
+                width: 50.0,
+                height: 50.0,
+                ease_in: 0.5,
+                ease_out: 0.5,
+            }],
+            ..ClipData::fixture(1, 0.0, 0.0, 2.0)
+        }];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export with zoom region should succeed: {:?}", result.err());
+
+        // Zooming must not change the output frame size
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert_eq!(metadata.width, 320);
+        assert_eq!(metadata.height, 240);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_transition() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("transition");
+        let _ = std::fs::remove_file(&output_path);
+
+        // The 0.5s slide overlaps the two 1s clips: 1.5s of output
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 1.0), ClipData::fixture(2, 1.0, 1.0, 2.0)];
+        let transitions = vec![TransitionData {
+            id: 1,
+            clip_id_before: 1,
+            clip_id_after: 2,
+            transition_type: "slide".to_string(),
+            duration: 0.5,
+            options: Default::default(),
+            audio_offset: 0.0,
+        }];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let plan = pipeline.plan_timeline(clips.clone(), transitions.clone(), vec![], vec![], vec![], settings.clone())
+            .expect("Dry run should succeed");
+        assert_eq!(plan.route, ExportRoute::Singletrack);
+        assert!((plan.expected_duration - 1.5).abs() < 0.001);
+        let final_graph = plan.commands.last().unwrap().filtergraph.clone().unwrap_or_default();
+        assert!(final_graph.contains("xfade=transition=slideleft:duration=0.500:offset=0.500"), "Transition should be rendered: {}", final_graph);
+
+        let result = pipeline.export_timeline(clips, transitions, vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export with a transition should succeed: {:?}", result.err());
+        let duration = probed_duration(&output_path);
+        assert!((duration - 1.5).abs() < 0.2, "Expected ~1.5s, got {}", duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_j_and_l_cuts() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("split_audio");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Second clip's audio starts 0.5s early (J-cut), third clip hears the second for 0.5s (L-cut)
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 1.0),
+            ClipData { audio_lead: 0.5, audio_lag: 0.5, ..ClipData::fixture(2, 1.0, 1.0, 2.0) },
+            ClipData::fixture(3, 2.0, 0.0, 1.0),
+        ];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export with J/L cuts should succeed: {:?}", result.err());
+
+        // Audio is mixed to the video length, so duration stays at 3 seconds
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert!((metadata.duration - 3.0).abs() < 0.2, "Expected ~3s, got {}", metadata.duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_unlinked_audio_and_video() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("unlinked");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Same media twice: video-only on track 0, audio-only from a later range on track 1
+        let clips = vec![
+            ClipData { audio_enabled: false, ..ClipData::fixture(1, 0.0, 0.0, 1.0) },
+            ClipData { track: 1, video_enabled: false, ..ClipData::fixture(2, 0.0, 1.0, 2.0) },
+        ];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Unlinked export should succeed: {:?}", result.err());
+
+        // The audio-only clip must not be composited as a picture-in-picture overlay
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert_eq!(metadata.width, 320);
+        assert!(metadata.audio_codec.is_some(), "Output should carry the audio-only clip's audio");
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_track_settings() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("track_settings");
+        let _ = std::fs::remove_file(&output_path);
+
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData { track: 1, ..ClipData::fixture(2, 0.0, 0.0, 1.0) }];
+
+        // Overlay track hidden but soloed and panned: only its audio should be heard
+        let track = |index: u32, solo: bool, hidden: bool, gain_db: f64, pan: f64| TrackData {
+            index,
+            muted: false,
+            solo,
+            hidden,
+            locked: false,
+            gain_db,
+            pan,
+            tag: None,
+        };
+        let tracks = vec![track(0, false, false, 0.0, 0.0), track(1, true, true, -3.0, -0.5)];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], tracks, settings);
+        assert!(result.is_ok(), "Export with track settings should succeed: {:?}", result.err());
+
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert_eq!(metadata.width, 320);
+        assert!((metadata.duration - 2.0).abs() < 0.2, "Expected ~2s, got {}", metadata.duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_with_master_bus() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("master_bus");
+        let _ = std::fs::remove_file(&output_path);
+
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0)];
+
+        // Full speech chain: high-pass, compressor, de-esser, limiter
         let settings = ExportSettings {
-            resolution: Resolution::Source,
-            output_path: output_path.to_str().unwrap().to_string(),
+            master: MasterBus {
+                high_pass_hz: Some(80.0),
+                compressor: Some(CompressorPreset::Speech),
+                de_esser: true,
+                limiter_ceiling_db: Some(-1.0),
+            },
+            ..test_settings(Resolution::Source, &output_path)
         };
 
         let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
         let pipeline = ExportPipeline::new(ffmpeg);
 
-        let result = pipeline.export_timeline(clips, settings);
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export with master bus should succeed: {:?}", result.err());
+
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert!(metadata.audio_codec.is_some(), "Output should have audio");
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_keeps_timeline_gaps() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("timeline_gaps");
+        let _ = std::fs::remove_file(&output_path);
+
+        // 0.5s gap at the start and 1s gap between the clips: output should be 3.5s
+        let clips = vec![ClipData::fixture(1, 0.5, 0.0, 1.0), ClipData::fixture(2, 2.5, 0.0, 1.0)];
+
+        let settings = ExportSettings {
+            gap_fill: GapFill {
+                color: "#202020".to_string(),
+                slate_path: None,
+            },
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export with gaps should succeed: {:?}", result.err());
+
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert!((metadata.duration - 3.5).abs() < 0.2, "Expected ~3.5s, got {}", metadata.duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_multitrack_overlay_extends_timeline() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("multitrack_extent");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Base is 1s long; overlays on tracks 1 and 2 overlap each other and run to 3s
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 1.0),
+            ClipData { track: 2, ..ClipData::fixture(2, 0.5, 0.0, 2.0) },
+            ClipData { track: 1, ..ClipData::fixture(3, 1.0, 0.0, 2.0) },
+        ];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
         assert!(result.is_ok(), "Multi-track export should succeed: {:?}", result.err());
+
+        // Output runs to the end of the last overlay, not the end of the base track
+        let wrapper = FFmpegWrapper::new().unwrap();
+        let metadata = wrapper.probe(output_path.to_str().unwrap())
+            .expect("Should probe exported video");
+        assert!((metadata.duration - 3.0).abs() < 0.2, "Expected ~3s, got {}", metadata.duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_multitrack_export_with_text_overlay() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("multitrack_text");
+        let _ = std::fs::remove_file(&output_path);
+
+        // The caption spans the overlap, so it has to be drawn on the composited picture
+        let clips = vec![
+            ClipData::fixture(1, 0.0, 0.0, 2.0),
+            ClipData { track: 1, ..ClipData::fixture(2, 0.5, 0.0, 1.0) },
+        ];
+        let text_overlays = vec![TextOverlayData {
+            id: 1,
+            text: "Caption".to_string(),
+            start_time: 0.25,
+            duration: 1.5,
+            x: 50.0,
+            y: 80.0,
+            font_size: 24,
+            font_family: "Arial".to_string(),
+            color: "#FFFFFF".to_string(),
+            animation: "none".to_string(),
+        }];
+
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let plan = pipeline.plan_timeline(clips.clone(), vec![], text_overlays.clone(), vec![], vec![], settings.clone())
+            .expect("Dry run should succeed");
+        assert_eq!(plan.route, ExportRoute::Multitrack);
+        let final_graph = plan.commands.last().unwrap().filtergraph.clone().unwrap_or_default();
+        assert!(final_graph.contains("drawtext="), "Captions should be drawn: {}", final_graph);
+
+        let result = pipeline.export_timeline(clips, vec![], text_overlays, vec![], vec![], settings);
+        assert!(result.is_ok(), "Multi-track export with text should succeed: {:?}", result.err());
         assert!(output_path.exists());
 
         // Clean up
         let _ = std::fs::remove_file(&output_path);
     }
+
+    #[test]
+    fn test_export_dry_run_plans_without_rendering() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("dry_run");
+        let script_path = std::env::temp_dir().join("clipforge_test_dry_run.sh");
+        let _ = std::fs::remove_file(&output_path);
+        let _ = std::fs::remove_file(&script_path);
+
+        // Two 1s clips with a 1s gap between them: 3s of output
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 1.0), ClipData::fixture(2, 2.0, 0.0, 1.0)];
+
+        let settings = ExportSettings {
+            dry_run: true,
+            script_path: Some(script_path.to_str().unwrap().to_string()),
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        let plan = pipeline.plan_timeline(clips, vec![], vec![], vec![], vec![], settings)
+            .expect("Dry run should succeed");
+        assert_eq!(plan.route, ExportRoute::Singletrack);
+        assert!((plan.expected_duration - 3.0).abs() < 0.001);
+        // Two clips and a gap are trimmed, then concatenated and encoded
+        assert_eq!(plan.commands.len(), 4);
+        assert_eq!(plan.intermediates.len(), 3);
+        assert!(plan.commands.last().unwrap().input_files.len() == 1, "Concat list should be captured");
+        assert!(!output_path.exists(), "A dry run must not render the output");
+
+        let script = std::fs::read_to_string(&script_path).expect("Script should be written");
+        assert!(script.contains("\"$FFMPEG\""));
+
+        // Clean up
+        let _ = std::fs::remove_file(&script_path);
+    }
+
+    /// Errors FFmpeg reports while decoding every frame of a file (empty when it decodes cleanly)
+    fn decode_errors(path: &Path) -> String {
+        let output = std::process::Command::new("ffmpeg")
+            .args(["-v", "error", "-i", path.to_str().unwrap(), "-f", "null", "-"])
+            .output()
+            .expect("Failed to run ffmpeg");
+        assert!(output.status.success(), "Decoding should succeed");
+        String::from_utf8_lossy(&output.stderr).to_string()
+    }
+
+    #[test]
+    fn test_smart_render_cuts_clips_between_keyframes() {
+        let test_video = get_test_video_path();
+        assert!(test_video.exists());
+
+        let output_path = get_test_output_path("smart_render");
+        let _ = std::fs::remove_file(&output_path);
+
+        // Smart render is on by default
+        let settings = test_settings(Resolution::Source, &output_path);
+
+        let ffmpeg = Arc::new(Mutex::new(FFmpegWrapper::new().unwrap()));
+        let pipeline = ExportPipeline::new(ffmpeg);
+
+        // The fixture's only keyframe is its first frame, so the first clip is re-encoded
+        // from its mid-GOP in point and the second is copied
+        let clips = vec![ClipData::fixture(1, 0.0, 0.5, 2.0), ClipData::fixture(2, 1.5, 0.0, 2.0)];
+        let plan = pipeline.plan_timeline(clips.clone(), vec![], vec![], vec![], vec![], settings.clone())
+            .expect("Dry run should succeed");
+        assert_eq!(plan.route, ExportRoute::SmartRender);
+
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        // Re-encoded and copied segments must decode together without errors, not just mux
+        assert_eq!(decode_errors(&output_path), "");
+        let duration = probed_duration(&output_path);
+        assert!((duration - 3.5).abs() < 0.2, "Expected ~3.5s, got {}", duration);
+
+        // Clean up
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_range() {
+        let output_path = get_test_output_path("range");
+        let _ = std::fs::remove_file(&output_path);
+
+        // 1s-3s of two back-to-back clips: the end of the first and the start of the second
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData::fixture(2, 2.0, 0.0, 2.0)];
+        let settings = ExportSettings {
+            range: Some(ExportRange { start: 1.0, end: 3.0 }),
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        let duration = probed_duration(&output_path);
+        assert!((duration - 2.0).abs() < 0.2, "Expected ~2s, got {}", duration);
+
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_gif() {
+        let output_path = get_test_output_path("animation").with_extension("gif");
+        let _ = std::fs::remove_file(&output_path);
+
+        let settings = ExportSettings {
+            animation: Some(AnimationSettings {
+                format: AnimationFormat::Gif,
+                fps: 10.0,
+                max_width: Some(160),
+                dither: Dither::Bayer,
+                bayer_scale: 2,
+                plays: 0,
+            }),
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(vec![ClipData::fixture(1, 0.0, 0.0, 2.0)], vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        let metadata = FFmpegWrapper::new().unwrap().probe(output_path.to_str().unwrap()).expect("GIF should probe");
+        assert_eq!(metadata.codec, "gif");
+        assert!(metadata.width <= 160, "GIF should be scaled down to 160px, got {}", metadata.width);
+        assert!((metadata.duration - 2.0).abs() < 0.3, "Expected ~2s, got {}", metadata.duration);
+
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_audio_only() {
+        let output_path = get_test_output_path("audio_only").with_extension("m4a");
+        let _ = std::fs::remove_file(&output_path);
+
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData::fixture(2, 2.0, 0.0, 2.0)];
+        let settings = ExportSettings {
+            audio_only: Some(AudioSettings { format: AudioFormat::Aac, bitrate_kbps: Some(128) }),
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        // The mix only: no video stream, and the length of the whole timeline
+        assert_eq!(probe_entries(&output_path, "stream=codec_type,codec_name"), vec!["aac,audio"]);
+        let duration = probed_format_duration(&output_path);
+        assert!((duration - 4.0).abs() < 0.2, "Expected ~4s, got {}", duration);
+
+        let _ = std::fs::remove_file(&output_path);
+    }
+
+    #[test]
+    fn test_export_stems() {
+        let output_path = get_test_output_path("stems").with_extension("mkv");
+        let stem_paths = [
+            output_path.with_file_name("clipforge_test_stems_voice.wav"),
+            output_path.with_file_name("clipforge_test_stems_music.wav"),
+        ];
+        let _ = std::fs::remove_file(&output_path);
+        for path in &stem_paths {
+            let _ = std::fs::remove_file(path);
+        }
+
+        let clips = vec![ClipData::fixture(1, 0.0, 0.0, 2.0), ClipData { track: 1, ..ClipData::fixture(2, 0.0, 0.0, 2.0) }];
+        let track = |index: u32, tag: &str| TrackData {
+            index,
+            muted: false,
+            solo: false,
+            hidden: false,
+            locked: false,
+            gain_db: 0.0,
+            pan: 0.0,
+            tag: Some(tag.to_string()),
+        };
+        let tracks = vec![track(0, "Voice"), track(1, "Music")];
+        let settings = ExportSettings {
+            stems: Some(StemSettings {
+                group_by: StemGrouping::Tag,
+                wav_files: true,
+                embed: true,
+                language: Some("eng".to_string()),
+            }),
+            ..test_settings(Resolution::Source, &output_path)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(clips, vec![], vec![], vec![], tracks, settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        // One WAV per tag, each as long as the timeline
+        for path in &stem_paths {
+            assert_eq!(probe_entries(path, "stream=codec_name"), vec!["pcm_s16le"], "{}", path.display());
+            let duration = probed_format_duration(path);
+            assert!((duration - 2.0).abs() < 0.2, "Expected ~2s for {}, got {}", path.display(), duration);
+        }
+
+        // The mix first, then the stems
+        assert_eq!(probe_entries(&output_path, "stream=codec_type"), vec!["video", "audio", "audio", "audio"]);
+        let titles: Vec<String> = probe_entries(&output_path, "stream_tags=title").into_iter()
+            .filter(|title| !title.is_empty())
+            .collect();
+        assert_eq!(titles, vec!["Mix", "voice", "music"]);
+
+        let _ = std::fs::remove_file(&output_path);
+        for path in &stem_paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_export_image_sequence() {
+        // The output path is a directory of frames
+        let output_dir = std::env::temp_dir().join("clipforge_test_sequence");
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        let settings = ExportSettings {
+            image_sequence: Some(ImageSequenceSettings {
+                format: SequenceFormat::Png,
+                alpha: true,
+                pattern: "frame_%05d".to_string(),
+                start_number: 1,
+                audio: true,
+            }),
+            ..test_settings(Resolution::Source, &output_dir)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(vec![ClipData::fixture(1, 0.0, 0.0, 2.0)], vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        // The fixture is 1 fps: two frames, numbered from start_number, and the mix named after the pattern
+        let mut files: Vec<String> = std::fs::read_dir(&output_dir).expect("Sequence directory should exist")
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["frame.wav", "frame_00001.png", "frame_00002.png"]);
+
+        assert_eq!(probe_entries(&output_dir.join("frame_00001.png"), "stream=pix_fmt"), vec!["rgba"]);
+        let duration = probed_format_duration(&output_dir.join("frame.wav"));
+        assert!((duration - 2.0).abs() < 0.2, "Expected ~2s of audio, got {}", duration);
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_export_hls() {
+        // The output path is a directory with the master playlist and a directory per rendition
+        let output_dir = std::env::temp_dir().join("clipforge_test_hls");
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        // The fixture is 240 pixels high, so the 720p rung is skipped rather than scaled up
+        let settings = ExportSettings {
+            streaming: Some(StreamingSettings {
+                renditions: vec![
+                    Rendition { height: 720, video_bitrate_kbps: 2800, audio_bitrate_kbps: 128 },
+                    Rendition { height: 144, video_bitrate_kbps: 300, audio_bitrate_kbps: 64 },
+                ],
+                hls: true,
+                dash: false,
+                segment_duration: 1.0,
+            }),
+            ..test_settings(Resolution::Source, &output_dir)
+        };
+
+        let pipeline = ExportPipeline::new(Arc::new(Mutex::new(FFmpegWrapper::new().unwrap())));
+        let result = pipeline.export_timeline(vec![ClipData::fixture(1, 0.0, 0.0, 2.0)], vec![], vec![], vec![], vec![], settings);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result.err());
+
+        let master = std::fs::read_to_string(output_dir.join("master.m3u8")).expect("Master playlist should exist");
+        assert!(master.contains("144p/playlist.m3u8"), "Master playlist should list the 144p rendition:\n{}", master);
+        assert!(!master.contains("720p"), "Master playlist shouldn't list the 720p rendition:\n{}", master);
+        assert!(!output_dir.join("720p").exists());
+
+        let playlist = std::fs::read_to_string(output_dir.join("144p/playlist.m3u8")).expect("Rendition playlist should exist");
+        assert!(playlist.contains("#EXT-X-ENDLIST"), "Playlist should be a finished VOD playlist:\n{}", playlist);
+        let segment = output_dir.join("144p/segment_00000.ts");
+        assert_eq!(probe_entries(&segment, "stream=codec_type,height"), vec!["video,144", "audio"]);
+
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
 }
@@ -171,6 +171,10 @@ export default function ExportDialog({ isOpen, onClose }) {
       is_muted: c.isMuted || false,
       fade_in_duration: c.fadeInDuration || 0,
       fade_out_duration: c.fadeOutDuration || 0,
+      audio_lead: c.audioLead || 0,  // J-cut: audio starts before the video
+      audio_lag: c.audioLag || 0,    // L-cut: audio continues past the video
+      video_enabled: c.videoEnabled ?? true,  // false = audio-only (unlinked)
+      audio_enabled: c.audioEnabled ?? true,  // false = video-only (unlinked)
     }));
 
     // Export settings
@@ -189,7 +193,8 @@ export default function ExportDialog({ isOpen, onClose }) {
         clip_id_before: t.clipIdBefore,
         clip_id_after: t.clipIdAfter,
         transition_type: t.type,
-        duration: t.duration
+        duration: t.duration,
+        audio_offset: t.audioOffset || 0
       }));
 
       // Prepare text overlay data for export
@@ -53,6 +53,7 @@ const REDO = 'REDO';
 const COPY_CLIP = 'COPY_CLIP';
 const CUT_CLIP = 'CUT_CLIP';
 const PASTE_CLIP = 'PASTE_CLIP';
+const UNLINK_CLIP = 'UNLINK_CLIP';
 
 // Helper: Create a snapshot of editable state (clips, overlays, and transitions)
 function createStateSnapshot(state) {
@@ -91,6 +92,9 @@ function timelineReducer(state, action) {
         isMuted: action.payload.isMuted || false,
         fadeInDuration: action.payload.fadeInDuration || 0, // seconds
         fadeOutDuration: action.payload.fadeOutDuration || 0, // seconds
+        // Unlinked clips use only one of their streams
+        videoEnabled: action.payload.videoEnabled ?? true,
+        audioEnabled: action.payload.audioEnabled ?? true,
       };
 
       return {
@@ -126,6 +130,35 @@ function timelineReducer(state, action) {
       };
     }
 
+    case UNLINK_CLIP: {
+      // Split a clip into a video-only clip (in place) and an audio-only copy on the next track
+      // The two halves can then be trimmed and moved independently
+      const clipId = action.payload;
+      const clip = state.clips.find(c => c.id === clipId);
+      if (!clip) return state;
+
+      const audioClip = {
+        ...structuredClone(clip),
+        id: state.nextClipId,
+        track: (clip.track || 0) + 1,
+        videoEnabled: false,
+        audioEnabled: true,
+      };
+
+      return {
+        ...state,
+        clips: [
+          ...state.clips.map(c =>
+            c.id === clipId ? { ...c, videoEnabled: true, audioEnabled: false } : c
+          ),
+          audioClip,
+        ],
+        nextClipId: state.nextClipId + 1,
+        undoStack: pushUndoStack(state.undoStack, createStateSnapshot(state)),
+        redoStack: [],
+      };
+    }
+
     case SELECT_CLIP: {
       return {
         ...state,
@@ -530,6 +563,10 @@ export function TimelineProvider({ children }) {
     dispatch({ type: PASTE_CLIP, payload: { playheadTime } });
   }, []);
 
+  const unlinkClip = useCallback((clipId) => {
+    dispatch({ type: UNLINK_CLIP, payload: clipId });
+  }, []);
+
   const value = {
     clips: state.clips,
     textOverlays: state.textOverlays,
@@ -565,6 +602,7 @@ export function TimelineProvider({ children }) {
     copyClip,
     cutClip,
     pasteClip,
+    unlinkClip,
     canUndo: state.undoStack.length > 0,
     canRedo: state.redoStack.length > 0,
     hasClipboard: state.clipboardClip !== null,
@@ -123,10 +123,50 @@ export async function importRecording(filePath, durationOverride = null) {
  * @param {Array} transitions - Array of transition data objects
  * @param {Array} textOverlays - Array of text overlay data objects
  * @param {Object} settings - Export settings (resolution, output_path)
- * @returns {Promise<string>} - Path to exported file
+ * @param {Array} redactions - Array of redaction region objects (blur/pixelate/box)
+ * @param {Array} tracks - Array of track settings ({index, muted, solo, hidden, locked, gain_db, pan, tag})
+ * @returns {Promise<string|Object>} - Path to exported file, or the render plan when settings.dry_run is set
  */
-export async function exportTimeline(clips, transitions, textOverlays, settings) {
-  return await invoke('export_timeline', { clips, transitions, textOverlays, settings });
+export async function exportTimeline(clips, transitions, textOverlays, settings, redactions = [], tracks = []) {
+  return await invoke('export_timeline', { clips, transitions, textOverlays, redactions, tracks, settings });
+}
+
+/**
+ * Render the composited timeline frame at a timestamp to an image
+ * @param {Object} timeline - { clips, transitions, textOverlays, redactions, tracks }
+ * @param {Object} settings - Export settings (resolution, layout, gap fill)
+ * @param {number} time - Timeline position in seconds
+ * @param {string} outputPath - Image file to write
+ * @param {string} format - 'png', 'jpeg' or 'webp'
+ * @returns {Promise<string>} - Path to the image
+ */
+export async function exportFrame(timeline, settings, time, outputPath, format = 'png') {
+  const { clips, transitions = [], textOverlays = [], redactions = [], tracks = [] } = timeline;
+  return await invoke('export_frame', { clips, transitions, textOverlays, redactions, tracks, settings, time, outputPath, format });
+}
+
+/**
+ * List available transitions and their options
+ * @returns {Promise<Array<{id: string, name: string, directions: string[], softness: boolean, color: boolean, expression: boolean}>>}
+ */
+export async function listTransitions() {
+  return await invoke('list_transitions');
+}
+
+/**
+ * Delete all cached intermediate renders
+ * @returns {Promise<number>} - Bytes freed
+ */
+export async function clearRenderCache() {
+  return await invoke('clear_render_cache');
+}
+
+/**
+ * Get what the FFmpeg build supports
+ * @returns {Promise<{version: string, encoders: string[], filters: string[], muxers: string[]}>}
+ */
+export async function getFfmpegCapabilities() {
+  return await invoke('get_ffmpeg_capabilities');
 }
 
 /**